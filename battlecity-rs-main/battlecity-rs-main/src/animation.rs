@@ -18,6 +18,8 @@ pub struct Animation {
     frame_duration: f32,
     frame_dt: f32,
     mode: AnimMode,
+    // tank this plays for, so it can be stopped along with it
+    player: Option<u32>,
 
     pos2d: [i32; 2],
     prev2d: [i32; 2],
@@ -45,6 +47,7 @@ impl Animation {
             frame_duration ,
             frame_dt: 0.0,
             mode ,
+            player: None,
             pos2d: position_2d,
             prev2d: position_2d,
         }
@@ -54,9 +57,19 @@ impl Animation {
         Self::new(position_2d, EXPLOSION_FRAMES.to_vec(), 0.10, AnimMode::Once)
     }
 
+    /// Loops until `Game` stops it, once the tank is done spawning.
     pub fn new_spawn(position_2d: [i32; 2]) -> Self {
         Self::new(position_2d, SPAWN_FRAMES.to_vec(), 0.10, AnimMode::Loop) }
 
+    pub fn for_player(mut self, player: u32) -> Self {
+        self.player = Some(player);
+        self
+    }
+
+    pub fn get_player(&self) -> Option<u32> {
+        self.player
+    }
+
     pub fn on_frame(&mut self, dt: f32){
         self.frame_dt += dt;
         if self.frame_dt >= self.frame_duration {
@@ -0,0 +1,226 @@
+//! How the bots play: a difficulty sets how sharp a bot is, a personality
+//! what it goes after. Both are picked per bot slot, in the rules file
+//! (`bots`) or from the menu; `Game` does the actual thinking.
+
+use serde::{Deserialize, Serialize};
+
+use crate::nav::Path;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotDifficulty {
+    Beginner,
+    #[default]
+    Normal,
+    Hard,
+    Veteran,
+}
+
+/// What a difficulty means in numbers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BotSkill {
+    /// Seconds between two decisions.
+    pub reaction_time: f64,
+    /// Seconds it waits after a shot before deciding on the next one.
+    pub fire_cooldown: f64,
+    /// Chance it turns to face a lined-up target before shooting; otherwise
+    /// the shot goes wherever it was looking.
+    pub aim_accuracy: f64,
+    /// Chance it steps out of the way of a bullet coming at it.
+    pub dodge_chance: f64,
+    /// Tiles it will drive out of its way for a pickup.
+    pub pickup_greed: u32,
+}
+
+impl BotDifficulty {
+    pub const ALL: [BotDifficulty; 4] = [
+        BotDifficulty::Beginner,
+        BotDifficulty::Normal,
+        BotDifficulty::Hard,
+        BotDifficulty::Veteran,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BotDifficulty::Beginner => "Beginner",
+            BotDifficulty::Normal => "Normal",
+            BotDifficulty::Hard => "Hard",
+            BotDifficulty::Veteran => "Veteran",
+        }
+    }
+
+    pub fn skill(self) -> BotSkill {
+        match self {
+            BotDifficulty::Beginner => BotSkill {
+                reaction_time: 0.45,
+                fire_cooldown: 1.2,
+                aim_accuracy: 0.5,
+                dodge_chance: 0.0,
+                pickup_greed: 2,
+            },
+            BotDifficulty::Normal => BotSkill {
+                reaction_time: 0.18,
+                fire_cooldown: 0.6,
+                aim_accuracy: 0.8,
+                dodge_chance: 0.3,
+                pickup_greed: 4,
+            },
+            BotDifficulty::Hard => BotSkill {
+                reaction_time: 0.12,
+                fire_cooldown: 0.45,
+                aim_accuracy: 0.9,
+                dodge_chance: 0.6,
+                pickup_greed: 6,
+            },
+            BotDifficulty::Veteran => BotSkill {
+                reaction_time: 0.08,
+                fire_cooldown: 0.3,
+                aim_accuracy: 1.0,
+                dodge_chance: 0.9,
+                pickup_greed: 8,
+            },
+        }
+    }
+
+    /// The one after this in `ALL`, wrapping around; for cycling in menus.
+    pub fn next(self) -> BotDifficulty {
+        let index = Self::ALL.iter().position(|&kind| kind == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotPersonality {
+    /// Goes straight for its target, pickups only when they are on the way.
+    #[default]
+    Hunter,
+    /// Holds its spawn and only comes out for targets that get close.
+    Camper,
+    /// Goes out of its way for every pickup, hunts in between.
+    Collector,
+    /// Tunnels through bricks rather than driving around them.
+    WallBreaker,
+}
+
+impl BotPersonality {
+    pub const ALL: [BotPersonality; 4] = [
+        BotPersonality::Hunter,
+        BotPersonality::Camper,
+        BotPersonality::Collector,
+        BotPersonality::WallBreaker,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BotPersonality::Hunter => "Hunter",
+            BotPersonality::Camper => "Camper",
+            BotPersonality::Collector => "Collector",
+            BotPersonality::WallBreaker => "Wall breaker",
+        }
+    }
+
+    /// The one after this in `ALL`, wrapping around; for cycling in menus.
+    pub fn next(self) -> BotPersonality {
+        let index = Self::ALL.iter().position(|&kind| kind == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Settings of one bot slot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotConfig {
+    pub difficulty: BotDifficulty,
+    pub personality: BotPersonality,
+}
+
+impl BotConfig {
+    pub fn skill(&self) -> BotSkill {
+        self.difficulty.skill()
+    }
+
+    /// Same personality, the next difficulty; for cycling in menus.
+    pub fn next_difficulty(mut self) -> Self {
+        self.difficulty = self.difficulty.next();
+        self
+    }
+
+    /// Same difficulty, the next personality; for cycling in menus.
+    pub fn next_personality(mut self) -> Self {
+        self.personality = self.personality.next();
+        self
+    }
+
+    /// Tiles away a pickup may be for this bot to go and get it.
+    pub fn pickup_reach(&self) -> u32 {
+        let greed = self.skill().pickup_greed;
+        match self.personality {
+            BotPersonality::Hunter | BotPersonality::Camper => greed / 2,
+            BotPersonality::Collector => greed * 3,
+            BotPersonality::WallBreaker => greed,
+        }
+    }
+}
+
+// what a bot remembers between ticks
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BotBrain {
+    pub(crate) config: BotConfig,
+    pub(crate) think_timer: f64,
+    pub(crate) fire_cd: f64,
+    pub(crate) path: Option<Path>,
+    // `Game::walls_revision` the path was planned on
+    pub(crate) path_revision: u64,
+    // id of the tank it is after
+    pub(crate) target: Option<u32>,
+    // who shot it last, remembered for `revenge_time` more seconds
+    pub(crate) last_attacker: Option<u32>,
+    pub(crate) revenge_time: f64,
+}
+
+impl BotBrain {
+    pub(crate) fn new(config: BotConfig) -> BotBrain {
+        BotBrain { config, ..BotBrain::default() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(difficulty: BotDifficulty, personality: BotPersonality) -> BotConfig {
+        BotConfig { difficulty, personality }
+    }
+
+    #[test]
+    fn pickup_reach_follows_the_personality() {
+        // normal greed is 4 tiles
+        assert_eq!(config(BotDifficulty::Normal, BotPersonality::Hunter).pickup_reach(), 2);
+        assert_eq!(config(BotDifficulty::Normal, BotPersonality::Camper).pickup_reach(), 2);
+        assert_eq!(config(BotDifficulty::Normal, BotPersonality::Collector).pickup_reach(), 12);
+        assert_eq!(config(BotDifficulty::Normal, BotPersonality::WallBreaker).pickup_reach(), 4);
+
+        assert_eq!(config(BotDifficulty::Beginner, BotPersonality::Hunter).pickup_reach(), 1);
+        assert_eq!(config(BotDifficulty::Veteran, BotPersonality::Collector).pickup_reach(), 24);
+    }
+
+    #[test]
+    fn harder_bots_are_sharper() {
+        for pair in BotDifficulty::ALL.windows(2) {
+            let (easier, harder) = (pair[0].skill(), pair[1].skill());
+            assert!(harder.reaction_time < easier.reaction_time, "{:?}", pair);
+            assert!(harder.fire_cooldown < easier.fire_cooldown, "{:?}", pair);
+            assert!(harder.aim_accuracy > easier.aim_accuracy, "{:?}", pair);
+            assert!(harder.dodge_chance > easier.dodge_chance, "{:?}", pair);
+            assert!(harder.pickup_greed > easier.pickup_greed, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn menu_cycling_keeps_the_other_half() {
+        let start = config(BotDifficulty::Veteran, BotPersonality::WallBreaker);
+        assert_eq!(start.next_difficulty(), config(BotDifficulty::Beginner, BotPersonality::WallBreaker));
+        assert_eq!(start.next_personality(), config(BotDifficulty::Veteran, BotPersonality::Hunter));
+    }
+}
@@ -0,0 +1,391 @@
+//! Importer for classic Battle City (NES) stages.
+//!
+//! A classic stage is 13x13 blocks. Each block is one hex digit, as in the
+//! original stage tables, and covers 2x2 of our tiles, so half bricks keep
+//! their shape:
+//!
+//! | digit | block               | digit | block               |
+//! |-------|---------------------|-------|---------------------|
+//! | `0`   | brick, right half   | `5`   | steel, right half   |
+//! | `1`   | brick, bottom half  | `6`   | steel, bottom half  |
+//! | `2`   | brick, left half    | `7`   | steel, left half    |
+//! | `3`   | brick, top half     | `8`   | steel, top half     |
+//! | `4`   | brick               | `9`   | steel               |
+//! | `A`   | water               | `B`   | forest              |
+//! | `C`   | ice                 | `D`-`F`, `.` | empty        |
+//!
+//! The eagle base and its brick fortress are not part of the stage data, the
+//! importer always adds them at the bottom centre like the original does. The
+//! result is a 28x28 `Level` (26x26 playfield plus a border).
+//!
+//! A tile is the smallest thing we draw or break, so bricks only exist at
+//! half-block resolution: there are no quarter-brick blocks, and a brick tile
+//! goes down as a whole where the original chips it away in smaller pieces.
+
+use std::fmt;
+
+use crate::{
+    level::{Level, SpawnPoint},
+    transform::LookDirection,
+    wall::WallType,
+};
+
+pub const CLASSIC_BLOCKS: usize = 13;
+
+/// Tiles per side of the imported level, playfield plus border.
+pub const CLASSIC_LEVEL_SIZE: u8 = (CLASSIC_BLOCKS * 2 + 2) as u8;
+
+/// Block of the eagle, column and row.
+const EAGLE_BLOCK: [usize; 2] = [6, 12];
+
+// player 1 spawns left of the base, the enemies along the top edge
+const SPAWN_BLOCKS: [([usize; 2], LookDirection); 4] = [
+    ([4, 12], LookDirection::Up),
+    ([6, 0], LookDirection::Down),
+    ([0, 0], LookDirection::Down),
+    ([12, 0], LookDirection::Down),
+];
+
+/// One quarter of a block, i.e. one tile of the imported level.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClassicTile {
+    Empty,
+    Brick,
+    Steel,
+    Water,
+    Forest,
+    Ice,
+    Eagle,
+}
+
+impl ClassicTile {
+    /// Our tile for it. There is no base to defend, the eagle is kept as an
+    /// indestructible block.
+    pub fn to_wall_type(self) -> WallType {
+        match self {
+            ClassicTile::Empty => WallType::Empty,
+            ClassicTile::Brick => WallType::Brick,
+            ClassicTile::Steel | ClassicTile::Eagle => WallType::Concrete,
+            ClassicTile::Water => WallType::Water,
+            ClassicTile::Forest => WallType::Forest,
+            ClassicTile::Ice => WallType::Ice,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClassicBlock {
+    BrickRight,
+    BrickBottom,
+    BrickLeft,
+    BrickTop,
+    Brick,
+    SteelRight,
+    SteelBottom,
+    SteelLeft,
+    SteelTop,
+    Steel,
+    Water,
+    Forest,
+    Ice,
+    Empty,
+}
+
+impl ClassicBlock {
+    pub fn from_digit(digit: char) -> Option<ClassicBlock> {
+        let block = match digit.to_ascii_uppercase() {
+            '0' => ClassicBlock::BrickRight,
+            '1' => ClassicBlock::BrickBottom,
+            '2' => ClassicBlock::BrickLeft,
+            '3' => ClassicBlock::BrickTop,
+            '4' => ClassicBlock::Brick,
+            '5' => ClassicBlock::SteelRight,
+            '6' => ClassicBlock::SteelBottom,
+            '7' => ClassicBlock::SteelLeft,
+            '8' => ClassicBlock::SteelTop,
+            '9' => ClassicBlock::Steel,
+            'A' => ClassicBlock::Water,
+            'B' => ClassicBlock::Forest,
+            'C' => ClassicBlock::Ice,
+            'D' | 'E' | 'F' | '.' => ClassicBlock::Empty,
+            _ => return None,
+        };
+        Some(block)
+    }
+
+    /// The 2x2 tiles this block covers, `[row][column]`.
+    pub fn quarters(self) -> [[ClassicTile; 2]; 2] {
+        use ClassicTile::{Brick, Empty, Steel};
+
+        let halves = |tile: ClassicTile, block: ClassicBlock| match block {
+            ClassicBlock::BrickRight | ClassicBlock::SteelRight => [[Empty, tile], [Empty, tile]],
+            ClassicBlock::BrickBottom | ClassicBlock::SteelBottom => [[Empty, Empty], [tile, tile]],
+            ClassicBlock::BrickLeft | ClassicBlock::SteelLeft => [[tile, Empty], [tile, Empty]],
+            ClassicBlock::BrickTop | ClassicBlock::SteelTop => [[tile, tile], [Empty, Empty]],
+            _ => [[tile; 2]; 2],
+        };
+
+        match self {
+            ClassicBlock::BrickRight
+            | ClassicBlock::BrickBottom
+            | ClassicBlock::BrickLeft
+            | ClassicBlock::BrickTop
+            | ClassicBlock::Brick => halves(Brick, self),
+            ClassicBlock::SteelRight
+            | ClassicBlock::SteelBottom
+            | ClassicBlock::SteelLeft
+            | ClassicBlock::SteelTop
+            | ClassicBlock::Steel => halves(Steel, self),
+            ClassicBlock::Water => [[ClassicTile::Water; 2]; 2],
+            ClassicBlock::Forest => [[ClassicTile::Forest; 2]; 2],
+            ClassicBlock::Ice => [[ClassicTile::Ice; 2]; 2],
+            ClassicBlock::Empty => [[Empty; 2]; 2],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ClassicError {
+    RowCount { found: usize },
+    RowLength { row: usize, found: usize },
+    UnknownBlock { row: usize, column: usize, block: char },
+    /// Stage data found before any `[name]` header in a stage collection.
+    MissingHeader { line: usize },
+    /// Error inside one stage of a collection.
+    InStage { stage: String, error: Box<ClassicError> },
+}
+
+impl fmt::Display for ClassicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassicError::RowCount { found } => {
+                write!(f, "a classic stage has {} rows, found {}", CLASSIC_BLOCKS, found)
+            }
+            ClassicError::RowLength { row, found } => write!(
+                f,
+                "row {} has {} blocks, a classic stage has {}",
+                row, found, CLASSIC_BLOCKS
+            ),
+            ClassicError::UnknownBlock { row, column, block } => write!(
+                f,
+                "unknown block '{}' at column {}, row {} (use 0-9, A-F or .)",
+                block, column, row
+            ),
+            ClassicError::MissingHeader { line } => {
+                write!(f, "line {}: stage data before any [stage name] header", line)
+            }
+            ClassicError::InStage { stage, error } => write!(f, "stage '{}': {}", stage, error),
+        }
+    }
+}
+
+impl std::error::Error for ClassicError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassicStage {
+    pub name: String,
+    /// `blocks[row][column]`.
+    pub blocks: [[ClassicBlock; CLASSIC_BLOCKS]; CLASSIC_BLOCKS],
+}
+
+impl ClassicStage {
+    /// Parses 13 lines of 13 digits; blank lines and `#` comments are skipped.
+    pub fn parse(name: &str, source: &str) -> Result<ClassicStage, ClassicError> {
+        let rows = source
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect::<Vec<_>>();
+        if rows.len() != CLASSIC_BLOCKS {
+            return Err(ClassicError::RowCount { found: rows.len() });
+        }
+
+        let mut blocks = [[ClassicBlock::Empty; CLASSIC_BLOCKS]; CLASSIC_BLOCKS];
+        for (y, line) in rows.iter().enumerate() {
+            let found = line.chars().count();
+            if found != CLASSIC_BLOCKS {
+                return Err(ClassicError::RowLength { row: y, found });
+            }
+            for (x, digit) in line.chars().enumerate() {
+                blocks[y][x] = ClassicBlock::from_digit(digit).ok_or(ClassicError::UnknownBlock {
+                    row: y,
+                    column: x,
+                    block: digit,
+                })?;
+            }
+        }
+
+        Ok(ClassicStage { name: name.to_string(), blocks })
+    }
+
+    /// Parses a collection of stages, each one introduced by a `[name]` line.
+    pub fn parse_all(source: &str) -> Result<Vec<ClassicStage>, ClassicError> {
+        let mut sections: Vec<(String, String)> = vec![];
+
+        for (index, line) in source.lines().enumerate() {
+            let trimmed = line.trim();
+            if let Some(name) = trimmed.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                sections.push((name.trim().to_string(), String::new()));
+            } else if let Some((_, body)) = sections.last_mut() {
+                body.push_str(line);
+                body.push('\n');
+            } else if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                return Err(ClassicError::MissingHeader { line: index + 1 });
+            }
+        }
+
+        sections
+            .iter()
+            .map(|(name, body)| {
+                ClassicStage::parse(name, body).map_err(|error| ClassicError::InStage {
+                    stage: name.clone(),
+                    error: Box::new(error),
+                })
+            })
+            .collect()
+    }
+
+    /// The stage at tile resolution, border not included, `[row][column]`.
+    pub fn tiles(&self) -> Vec<Vec<ClassicTile>> {
+        let size = CLASSIC_BLOCKS * 2;
+        let mut tiles = vec![vec![ClassicTile::Empty; size]; size];
+
+        for (by, row) in self.blocks.iter().enumerate() {
+            for (bx, block) in row.iter().enumerate() {
+                for (qy, quarter_row) in block.quarters().iter().enumerate() {
+                    for (qx, &tile) in quarter_row.iter().enumerate() {
+                        tiles[by * 2 + qy][bx * 2 + qx] = tile;
+                    }
+                }
+            }
+        }
+
+        // the base: eagle plus a one tile brick ring, open towards the bottom edge
+        let [ex, ey] = [EAGLE_BLOCK[0] * 2, EAGLE_BLOCK[1] * 2];
+        for row in &mut tiles[(ey - 1)..(ey + 2)] {
+            row[(ex - 1)..(ex + 3)].fill(ClassicTile::Brick);
+        }
+        for row in &mut tiles[ey..(ey + 2)] {
+            row[ex..(ex + 2)].fill(ClassicTile::Eagle);
+        }
+
+        // spawns are always drivable, whatever the data says
+        for ([bx, by], _) in SPAWN_BLOCKS {
+            for row in &mut tiles[(by * 2)..(by * 2 + 2)] {
+                row[(bx * 2)..(bx * 2 + 2)].fill(ClassicTile::Empty);
+            }
+        }
+
+        tiles
+    }
+
+    /// Converts to our level format: the playfield framed by a border, four
+    /// spawns (player 1 next to the base, enemies at the top) and no pickup spots.
+    pub fn to_level(&self) -> Level {
+        let size = CLASSIC_LEVEL_SIZE as usize;
+        let mut walls = vec![vec![WallType::Border; size]; size];
+
+        for (y, row) in self.tiles().iter().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
+                walls[y + 1][x + 1] = tile.to_wall_type();
+            }
+        }
+
+        let spawns = SPAWN_BLOCKS
+            .iter()
+            .map(|&([bx, by], facing)| SpawnPoint {
+                position: [bx as i32 * 2 + 1, by as i32 * 2 + 1],
+                facing,
+            })
+            .collect();
+
+        Level {
+            name: self.name.clone(),
+            width: CLASSIC_LEVEL_SIZE,
+            height: CLASSIC_LEVEL_SIZE,
+            tiles: walls,
+            spawns,
+            pickups: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::char_to_tile;
+
+    fn stage(rows: [&str; CLASSIC_BLOCKS]) -> ClassicStage {
+        ClassicStage::parse("test", &rows.join("\n")).unwrap()
+    }
+
+    fn level_row(chars: &str) -> Vec<WallType> {
+        chars.chars().map(|c| char_to_tile(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn reads_every_digit() {
+        assert_eq!(ClassicBlock::from_digit('0'), Some(ClassicBlock::BrickRight));
+        assert_eq!(ClassicBlock::from_digit('4'), Some(ClassicBlock::Brick));
+        assert_eq!(ClassicBlock::from_digit('8'), Some(ClassicBlock::SteelTop));
+        assert_eq!(ClassicBlock::from_digit('9'), Some(ClassicBlock::Steel));
+        assert_eq!(ClassicBlock::from_digit('a'), Some(ClassicBlock::Water));
+        assert_eq!(ClassicBlock::from_digit('B'), Some(ClassicBlock::Forest));
+        assert_eq!(ClassicBlock::from_digit('C'), Some(ClassicBlock::Ice));
+        for digit in ['D', 'E', 'f', '.'] {
+            assert_eq!(ClassicBlock::from_digit(digit), Some(ClassicBlock::Empty));
+        }
+        assert_eq!(ClassicBlock::from_digit('G'), None);
+        assert_eq!(ClassicBlock::from_digit(' '), None);
+    }
+
+    #[test]
+    fn halves_cover_the_right_quarters() {
+        use ClassicTile::{Brick, Empty, Steel};
+
+        assert_eq!(ClassicBlock::BrickRight.quarters(), [[Empty, Brick], [Empty, Brick]]);
+        assert_eq!(ClassicBlock::BrickBottom.quarters(), [[Empty, Empty], [Brick, Brick]]);
+        assert_eq!(ClassicBlock::SteelLeft.quarters(), [[Steel, Empty], [Steel, Empty]]);
+        assert_eq!(ClassicBlock::SteelTop.quarters(), [[Steel, Steel], [Empty, Empty]]);
+        assert_eq!(ClassicBlock::Steel.quarters(), [[Steel; 2]; 2]);
+        assert_eq!(ClassicBlock::Water.quarters(), [[ClassicTile::Water; 2]; 2]);
+        assert_eq!(ClassicBlock::Empty.quarters(), [[Empty; 2]; 2]);
+    }
+
+    #[test]
+    fn converts_a_stage_row() {
+        let mut rows = ["............."; CLASSIC_BLOCKS];
+        rows[1] = "40213956789AB";
+        let level = stage(rows).to_level();
+
+        assert_eq!(level.width, CLASSIC_LEVEL_SIZE);
+        assert_eq!(level.height, CLASSIC_LEVEL_SIZE);
+        // block row 1 is tile rows 2 and 3, one more for the border
+        assert_eq!(level.tiles[3], level_row("XBB.BB...BBCC.C..C.CCCCWWFFX"));
+        assert_eq!(level.tiles[4], level_row("XBB.BB.BB..CC.CCCC...CCWWFFX"));
+    }
+
+    #[test]
+    fn adds_the_fortress_and_clears_the_spawns() {
+        let level = stage(["9999999999999"; CLASSIC_BLOCKS]).to_level();
+
+        // eagle at block (6, 12), ringed by brick on three sides, border below
+        assert_eq!(&level.tiles[24][12..16], level_row("BBBB").as_slice());
+        assert_eq!(&level.tiles[25][12..16], level_row("BCCB").as_slice());
+        assert_eq!(&level.tiles[26][12..16], level_row("BCCB").as_slice());
+        assert_eq!(&level.tiles[27][12..16], level_row("XXXX").as_slice());
+
+        let spawns = level.spawns.iter().map(|spawn| (spawn.position, spawn.facing)).collect::<Vec<_>>();
+        assert_eq!(spawns, vec![
+            ([9, 25], LookDirection::Up),
+            ([13, 1], LookDirection::Down),
+            ([1, 1], LookDirection::Down),
+            ([25, 1], LookDirection::Down),
+        ]);
+        for ([x, y], _) in spawns {
+            let [x, y] = [x as usize, y as usize];
+            assert_eq!(&level.tiles[y][x..(x + 2)], level_row("..").as_slice());
+            assert_eq!(&level.tiles[y + 1][x..(x + 2)], level_row("..").as_slice());
+        }
+    }
+}
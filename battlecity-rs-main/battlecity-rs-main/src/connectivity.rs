@@ -0,0 +1,297 @@
+//! Reachability between spawns.
+//!
+//! Tanks drive over every tile that does not block them, can shoot their way
+//! through destructible ones (bricks) and never get past the rest (concrete,
+//! water, the border). `check` finds the cheapest route between every pair of
+//! spawns under those rules, `repair` carves through whatever seals a spawn
+//! off so that every pair has one.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{
+    nav,
+    wall::{Wall, WallType},
+};
+
+// only used while carving: concrete and water are cut before the border is
+const CONCRETE_CARVE_COST: u32 = 50;
+const BORDER_CARVE_COST: u32 = 200;
+
+/// Cheapest way from one spawn to another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Route {
+    pub cost: u32,
+    /// Tiles entered, the start tile not included.
+    pub length: usize,
+    /// Bricks (destructible tiles) that have to be shot along the way.
+    pub bricks: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectivityReport {
+    pub spawns: Vec<[i32; 2]>,
+    /// `routes[i][j]`: route from spawn `i` to spawn `j`, `None` when sealed off.
+    pub routes: Vec<Vec<Option<Route>>>,
+}
+
+impl ConnectivityReport {
+    /// Every spawn can reach every other one, bricks allowed.
+    pub fn is_connected(&self) -> bool {
+        self.unreachable_pairs().is_empty()
+    }
+
+    /// Pairs of spawn indices `(i, j)`, `i < j`, without any route.
+    pub fn unreachable_pairs(&self) -> Vec<(usize, usize)> {
+        let count = self.spawns.len();
+        (0..count)
+            .flat_map(|i| ((i + 1)..count).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.routes[i][j].is_none())
+            .collect()
+    }
+
+    /// Pairs that are connected, but only by shooting through bricks.
+    pub fn brick_only_pairs(&self) -> Vec<(usize, usize)> {
+        let count = self.spawns.len();
+        (0..count)
+            .flat_map(|i| ((i + 1)..count).map(move |j| (i, j)))
+            .filter(|&(i, j)| matches!(&self.routes[i][j], Some(route) if route.bricks > 0))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepairReport {
+    /// Tiles that were turned empty, in carving order.
+    pub carved: Vec<[i32; 2]>,
+    /// Connectivity after the repair.
+    pub report: ConnectivityReport,
+}
+
+// driving costs as in `nav`, plus a price on what carving may cut
+fn enter_cost(wall: &Wall, carving: bool) -> Option<u32> {
+    if let Some(cost) = nav::enter_cost(wall) {
+        Some(cost)
+    } else if !carving {
+        None
+    } else if wall.variant() == WallType::Border {
+        Some(BORDER_CARVE_COST)
+    } else {
+        Some(CONCRETE_CARVE_COST)
+    }
+}
+
+// carving empties what can neither be driven over nor shot away
+fn is_carvable(wall: &Wall) -> bool {
+    let flags = wall.flags();
+    flags.blocks_tanks && !flags.is_destructible()
+}
+
+// Dijkstra from `start`; returns cost and predecessor per tile, row-major
+fn cheapest_paths(
+    walls: &[Vec<Wall>],
+    start: [i32; 2],
+    carving: bool,
+) -> (Vec<Option<u32>>, Vec<Option<usize>>) {
+    let height = walls.len();
+    let width = walls.first().map_or(0, |row| row.len());
+    let mut cost = vec![None; width * height];
+    let mut previous = vec![None; width * height];
+
+    let [sx, sy] = start;
+    if sx < 0 || sy < 0 || sx as usize >= width || sy as usize >= height {
+        return (cost, previous);
+    }
+
+    let start = sy as usize * width + sx as usize;
+    cost[start] = Some(0);
+    let mut queue = BinaryHeap::from([Reverse((0, start))]);
+
+    while let Some(Reverse((current_cost, index))) = queue.pop() {
+        if cost[index].is_some_and(|known| known < current_cost) {
+            continue;
+        }
+
+        let (x, y) = (index % width, index / width);
+        let neighbours = [
+            (x > 0).then(|| index - 1),
+            (x + 1 < width).then(|| index + 1),
+            (y > 0).then(|| index - width),
+            (y + 1 < height).then(|| index + width),
+        ];
+
+        for next in neighbours.into_iter().flatten() {
+            let Some(step) = enter_cost(&walls[next / width][next % width], carving) else { continue };
+
+            let next_cost = current_cost + step;
+            if cost[next].is_none_or(|known| next_cost < known) {
+                cost[next] = Some(next_cost);
+                previous[next] = Some(index);
+                queue.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    (cost, previous)
+}
+
+// tiles from the start (excluded) to `end` (included)
+fn trace(previous: &[Option<usize>], end: usize) -> Vec<usize> {
+    let mut path = vec![end];
+    let mut current = end;
+    while let Some(before) = previous[current] {
+        path.push(before);
+        current = before;
+    }
+    path.pop();
+    path.reverse();
+    path
+}
+
+/// Cheapest routes between all pairs of `spawns` on `walls` (`walls[y][x]`).
+pub fn check(walls: &[Vec<Wall>], spawns: &[[i32; 2]]) -> ConnectivityReport {
+    let width = walls.first().map_or(0, |row| row.len());
+    let index_of = |[x, y]: [i32; 2]| {
+        (x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < walls.len())
+            .then(|| y as usize * width + x as usize)
+    };
+
+    let routes = spawns
+        .iter()
+        .map(|&from| {
+            let (cost, previous) = cheapest_paths(walls, from, false);
+            spawns
+                .iter()
+                .map(|&to| {
+                    let end = index_of(to)?;
+                    let total = cost[end]?;
+                    let path = trace(&previous, end);
+                    let bricks = path
+                        .iter()
+                        .filter(|&&i| walls[i / width][i % width].flags().is_destructible())
+                        .count();
+                    Some(Route { cost: total, length: path.len(), bricks })
+                })
+                .collect()
+        })
+        .collect();
+
+    ConnectivityReport { spawns: spawns.to_vec(), routes }
+}
+
+/// Makes every spawn reachable from the first one (and so from each other) by
+/// emptying the concrete or water, or as a last resort border, on the cheapest way
+/// through. Destructible tiles are left alone, they can be shot away.
+pub fn repair(walls: &mut [Vec<Wall>], spawns: &[[i32; 2]]) -> RepairReport {
+    let mut carved = vec![];
+    let width = walls.first().map_or(0, |row| row.len());
+
+    if let Some(&first) = spawns.first() {
+        for &target in &spawns[1..] {
+            let (cost, _) = cheapest_paths(walls, first, false);
+            let [tx, ty] = target;
+            if tx < 0 || ty < 0 || tx as usize >= width || ty as usize >= walls.len() {
+                continue;
+            }
+            let end = ty as usize * width + tx as usize;
+            if cost[end].is_some() {
+                continue;
+            }
+
+            let (_, previous) = cheapest_paths(walls, first, true);
+            for index in trace(&previous, end) {
+                let (x, y) = ((index % width) as i32, (index / width) as i32);
+                let wall = &mut walls[y as usize][x as usize];
+                if is_carvable(wall) {
+                    *wall = Wall::new([x, y]).empty();
+                    carved.push([x, y]);
+                }
+            }
+        }
+    }
+
+    RepairReport { carved, report: check(walls, spawns) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::char_to_tile;
+
+    // `rows` drawn as in level files
+    fn walls(rows: &[&str]) -> Vec<Vec<Wall>> {
+        rows.iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(|(x, tile)| {
+                        Wall::new([x as i32, y as i32]).with_variant(char_to_tile(tile).unwrap())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    const SEALED: [&str; 5] = [
+        "XXXXXXX",
+        "X.C...X",
+        "X.C...X",
+        "X.C...X",
+        "XXXXXXX",
+    ];
+
+    #[test]
+    fn check_reports_a_sealed_spawn() {
+        let report = check(&walls(&SEALED), &[[1, 1], [5, 3]]);
+        assert!(!report.is_connected());
+        assert_eq!(report.unreachable_pairs(), vec![(0, 1)]);
+        assert_eq!(report.routes[0][1], None);
+        assert_eq!(report.routes[0][0], Some(Route { cost: 0, length: 0, bricks: 0 }));
+    }
+
+    #[test]
+    fn check_counts_bricks_on_the_way() {
+        let report = check(&walls(&["XXXXX", "X.B.X", "XXXXX"]), &[[1, 1], [3, 1]]);
+        assert!(report.is_connected());
+        assert_eq!(report.brick_only_pairs(), vec![(0, 1)]);
+        let route = report.routes[0][1].as_ref().unwrap();
+        assert_eq!((route.length, route.bricks), (2, 1));
+    }
+
+    #[test]
+    fn repair_carves_a_way_and_reports_it() {
+        let mut walls = walls(&SEALED);
+        let repaired = repair(&mut walls, &[[1, 1], [5, 3]]);
+
+        assert_eq!(repaired.carved.len(), 1);
+        let [x, y] = repaired.carved[0];
+        assert_eq!(x, 2);
+        assert_eq!(walls[y as usize][x as usize].variant(), WallType::Empty);
+        assert!(repaired.report.is_connected());
+    }
+
+    #[test]
+    fn repair_leaves_connected_maps_alone() {
+        let mut walls = walls(&["XXXXX", "X.B.X", "XXXXX"]);
+        let repaired = repair(&mut walls, &[[1, 1], [3, 1]]);
+        assert!(repaired.carved.is_empty());
+        assert_eq!(walls[1][2].variant(), WallType::Brick);
+    }
+
+    #[test]
+    fn repair_cuts_concrete_and_water_before_the_border() {
+        // straight across is a single border tile, around it three tiles of
+        // concrete and water
+        let mut walls = walls(&[
+            "XXXXXXX",
+            "X..X..X",
+            "XCCWCCX",
+            "XXXXXXX",
+        ]);
+        let repaired = repair(&mut walls, &[[1, 1], [5, 1]]);
+
+        assert_eq!(repaired.carved, vec![[2, 2], [3, 2], [4, 2]]);
+        assert_eq!(walls[1][3].variant(), WallType::Border);
+        assert!(repaired.report.is_connected());
+    }
+}
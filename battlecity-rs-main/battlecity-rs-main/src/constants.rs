@@ -22,16 +22,64 @@ pub const SCOREBOARD_HEIGHT: f64 = 16.0;
 
 pub const GAME_TICK_INTERVAL: f64 = 0.1;
 
+pub const COUNTDOWN_DURATION: f64 = 3.0;
+pub const ROUND_OVER_DURATION: f64 = 3.0;
+pub const ROUNDS_TO_WIN: u32 = 2;
+
+// random maps: share of wall tiles, and how many of those are concrete / net
+pub const WALL_DENSITY: f64 = 0.65;
+pub const CONCRETE_RATIO: f64 = 0.25;
+pub const NET_RATIO: f64 = 0.25;
+
+// seconds before a damaged or destroyed wall grows back, 0 turns it off
+pub const WALL_REGEN_TIME: f64 = 0.0;
+
 pub const PLAYER_SHOT_INTERVAL: f64 = 0.5;
 pub const PLAYER_MAX_HEALTH: u32 = 3;
 pub const PLAYER_SPAWN_HEALTH: u32 = 3;
 pub const PLAYER_MAX_ARMOR: u32 = 3;
 pub const PLAYER_LIVES: u32 = 3;
 pub const PLAYER_SPAWN_ARMOR: u32 = 0;
+// seconds a tank cannot be hurt after (re)spawning
+pub const SPAWN_PROTECTION_TIME: f64 = 2.0;
+// seconds a destroyed tank waits before it comes back
+pub const RESPAWN_DELAY: f64 = 3.0;
+// seconds a respawning tank sparkles at its spawn before it can move or be seen
+pub const SPAWN_ANIMATION_TIME: f64 = 0.9;
+// how many ticks ahead a spawn in a bullet's way counts as unsafe
+pub const SPAWN_BULLET_LOOKAHEAD: u32 = 4;
+
+// campers leave their spawn for targets this many tiles from it
+pub const BOT_CAMP_RANGE: i32 = 6;
+// how many ticks ahead a bot sees bullets coming at it
+pub const BOT_DODGE_LOOKAHEAD: u32 = 4;
+// what a brick hit costs a wall breaker's path, see `nav::BRICK_HIT_COST`
+pub const WALL_BREAKER_BRICK_COST: u32 = 1;
+// picking a target: tiles of distance a tank is worth less for being a
+// threat (lined up and facing the bot), for having shot it lately, and for
+// being the one already chased
+pub const BOT_THREAT_BONUS: i32 = 6;
+pub const BOT_REVENGE_BONUS: i32 = 10;
+pub const BOT_TARGET_STICKINESS: i32 = 3;
+// seconds a bot holds a grudge against whoever shot it
+pub const BOT_REVENGE_TIME: f64 = 5.0;
 
 pub const MAX_SPAWNED_PICKUPS: usize = 5;
 pub const ARMOR_SPAWN_TIME: f64 = 25.0;
 pub const HEALTH_SPAWN_TIME: f64 = 10.0;
+pub const STAR_SPAWN_TIME: f64 = 30.0;
+pub const SHOVEL_SPAWN_TIME: f64 = 45.0;
+pub const GRENADE_SPAWN_TIME: f64 = 70.0;
+pub const CLOCK_SPAWN_TIME: f64 = 50.0;
+pub const HELMET_SPAWN_TIME: f64 = 35.0;
+pub const TANK_SPAWN_TIME: f64 = 90.0;
+
+// how long the timed power-ups last, seconds
+pub const SHOVEL_DURATION: f64 = 15.0;
+pub const CLOCK_DURATION: f64 = 8.0;
+pub const HELMET_DURATION: f64 = 10.0;
+// stars stack up to this tier: faster bullets, faster reload, concrete-breaking shots
+pub const MAX_STAR_TIER: u32 = 3;
 
 pub const GAME_OVER_TEXTURE_PATH: &str = "resources/gameover.png";
 pub const TANKS_TEXTURE_PATH: &str = "resources/tanks.png";
@@ -324,6 +372,62 @@ pub const ARMORED_TANK_4_TILE_LEFT: [f64; 4] = [
     TILE_SIZE - 0.5,
     TILE_SIZE - 0.5,
 ];
+// chipped walls, brick loses a bit more with every hit
+pub const BRICK_DAMAGED_1_TILE: [f64; 4] = [
+    0.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+pub const BRICK_DAMAGED_2_TILE: [f64; 4] = [
+    1.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+pub const CONCRETE_DAMAGED_TILE: [f64; 4] = [
+    2.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+
+pub const STAR_PICKUP_TILE: [f64; 4] = [
+    3.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+pub const SHOVEL_PICKUP_TILE: [f64; 4] = [
+    4.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+pub const GRENADE_PICKUP_TILE: [f64; 4] = [
+    5.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+pub const CLOCK_PICKUP_TILE: [f64; 4] = [
+    6.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+pub const HELMET_PICKUP_TILE: [f64; 4] = [
+    7.0 * TILE_SIZE,
+    6.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
+pub const TANK_PICKUP_TILE: [f64; 4] = [
+    0.0 * TILE_SIZE,
+    7.0 * TILE_SIZE,
+    TILE_SIZE - 0.5,
+    TILE_SIZE - 0.5,
+];
 
 pub const TANK_1_TILES: [[f64; 4]; 8] = [
     TANK_1_TILE_UP,
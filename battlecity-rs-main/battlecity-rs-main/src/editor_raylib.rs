@@ -0,0 +1,321 @@
+use std::path::PathBuf;
+
+use raylib::prelude::*;
+
+use battlecity::level::{Level, SpawnPoint, MAX_SPAWNS};
+use battlecity::transform::LookDirection;
+use battlecity::wall::WallType;
+
+use crate::render_raylib::tile_color;
+
+const PANEL_WIDTH: i32 = 260;
+const MARGIN: i32 = 20;
+const STATUS_TTL: f32 = 3.0;
+
+const SPAWN_COLORS: [Color; MAX_SPAWNS] = [Color::YELLOW, Color::BLUE, Color::PURPLE, Color::RED];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Brush {
+    Tile(WallType),
+    Spawn,
+    Pickup,
+}
+
+impl Brush {
+    fn label(self) -> &'static str {
+        match self {
+            Brush::Tile(WallType::Empty) => "Empty",
+            Brush::Tile(WallType::Brick) => "Brick",
+            Brush::Tile(WallType::Concrete) => "Concrete",
+            Brush::Tile(WallType::Net) => "Net",
+            Brush::Tile(WallType::Border) => "Border",
+            Brush::Tile(WallType::Water) => "Water",
+            Brush::Tile(WallType::Ice) => "Ice",
+            Brush::Tile(WallType::Forest) => "Forest",
+            Brush::Spawn => "Spawn",
+            Brush::Pickup => "Pickup spot",
+        }
+    }
+}
+
+const BRUSH_KEYS: [(KeyboardKey, Brush); 10] = [
+    (KeyboardKey::KEY_ONE,   Brush::Tile(WallType::Empty)),
+    (KeyboardKey::KEY_TWO,   Brush::Tile(WallType::Brick)),
+    (KeyboardKey::KEY_THREE, Brush::Tile(WallType::Concrete)),
+    (KeyboardKey::KEY_FOUR,  Brush::Tile(WallType::Net)),
+    (KeyboardKey::KEY_FIVE,  Brush::Tile(WallType::Border)),
+    (KeyboardKey::KEY_SIX,   Brush::Spawn),
+    (KeyboardKey::KEY_SEVEN, Brush::Pickup),
+    (KeyboardKey::KEY_EIGHT, Brush::Tile(WallType::Water)),
+    (KeyboardKey::KEY_NINE,  Brush::Tile(WallType::Ice)),
+    (KeyboardKey::KEY_ZERO,  Brush::Tile(WallType::Forest)),
+];
+
+/// What the client should do after the editor handled a frame.
+pub enum EditorAction {
+    None,
+    /// Play the level right away, the editor stays around to come back to.
+    TestPlay(Level),
+    Close,
+}
+
+/// Top-down level editor: paints tiles, spawns and pickup spots onto a
+/// `Level` and saves it in the level file format.
+pub struct Editor {
+    level: Level,
+    path: PathBuf,
+    brush: Brush,
+    slot: usize,
+    status: String,
+    status_ttl: f32,
+}
+
+impl Editor {
+    pub fn new(level: Level, path: PathBuf) -> Editor {
+        Editor {
+            level,
+            path,
+            brush: Brush::Tile(WallType::Brick),
+            slot: 0,
+            status: String::new(),
+            status_ttl: 0.0,
+        }
+    }
+
+    fn set_status(&mut self, status: impl Into<String>) {
+        self.status = status.into();
+        self.status_ttl = STATUS_TTL;
+    }
+
+    // largest square tile that fits the grid next to the panel
+    fn layout(&self, screen_w: i32, screen_h: i32) -> (Vector2, i32) {
+        let tile_px = ((screen_w - PANEL_WIDTH - MARGIN * 2) / self.level.width as i32)
+            .min((screen_h - MARGIN * 2) / self.level.height as i32)
+            .max(4);
+        (Vector2::new(MARGIN as f32, MARGIN as f32), tile_px)
+    }
+
+    fn hovered_tile(&self, rl: &RaylibHandle) -> Option<[i32; 2]> {
+        let (origin, tile_px) = self.layout(rl.get_screen_width(), rl.get_screen_height());
+        let mouse = rl.get_mouse_position();
+        let x = ((mouse.x - origin.x) / tile_px as f32).floor() as i32;
+        let y = ((mouse.y - origin.y) / tile_px as f32).floor() as i32;
+        self.level.is_in_bounds([x, y]).then_some([x, y])
+    }
+
+    pub fn update(&mut self, rl: &RaylibHandle, dt: f32) -> EditorAction {
+        self.status_ttl = (self.status_ttl - dt).max(0.0);
+
+        let ctrl = rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
+            || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+
+        if ctrl && rl.is_key_pressed(KeyboardKey::KEY_S) {
+            self.save();
+        } else if ctrl && rl.is_key_pressed(KeyboardKey::KEY_L) {
+            self.reload();
+        } else if ctrl && rl.is_key_pressed(KeyboardKey::KEY_N) {
+            self.clear();
+        }
+
+        for (key, brush) in BRUSH_KEYS {
+            if rl.is_key_pressed(key) {
+                self.brush = brush;
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_TAB) {
+            self.slot = (self.slot + 1) % (self.level.spawns.len() + 1).min(MAX_SPAWNS);
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F) {
+            if let Some(spawn) = self.level.spawns.get_mut(self.slot) {
+                spawn.facing = match spawn.facing {
+                    LookDirection::Up => LookDirection::Right,
+                    LookDirection::Right => LookDirection::Down,
+                    LookDirection::Down => LookDirection::Left,
+                    LookDirection::Left => LookDirection::Up,
+                };
+            }
+        }
+
+        if let Some(tile) = self.hovered_tile(rl) {
+            if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT) {
+                self.paint(tile);
+            } else if rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_RIGHT) {
+                self.erase(tile);
+            }
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_F5) {
+            return match self.level.validate() {
+                Ok(()) => EditorAction::TestPlay(self.level.clone()),
+                Err(err) => {
+                    self.set_status(format!("cannot play: {}", err));
+                    EditorAction::None
+                }
+            };
+        }
+        if rl.is_key_pressed(KeyboardKey::KEY_F2) {
+            return EditorAction::Close;
+        }
+        EditorAction::None
+    }
+
+    fn paint(&mut self, [x, y]: [i32; 2]) {
+        match self.brush {
+            Brush::Tile(tile) => self.level.tiles[y as usize][x as usize] = tile,
+            Brush::Spawn => {
+                // moving a slot keeps its facing, a new one looks up
+                let facing = self.level.spawns.get(self.slot).map_or(LookDirection::Up, |s| s.facing);
+                let spawn = SpawnPoint { position: [x, y], facing };
+                match self.level.spawns.get_mut(self.slot) {
+                    Some(existing) => *existing = spawn,
+                    None => {
+                        self.level.spawns.push(spawn);
+                        self.slot = self.level.spawns.len() - 1;
+                    }
+                }
+            }
+            Brush::Pickup => {
+                if !self.level.pickups.contains(&[x, y]) {
+                    self.level.pickups.push([x, y]);
+                }
+            }
+        }
+    }
+
+    fn erase(&mut self, tile: [i32; 2]) {
+        match self.brush {
+            Brush::Tile(_) => self.level.tiles[tile[1] as usize][tile[0] as usize] = WallType::Empty,
+            Brush::Spawn => {
+                // later slots move up one
+                self.level.spawns.retain(|spawn| spawn.position != tile);
+                self.slot = self.slot.min(self.level.spawns.len());
+            }
+            Brush::Pickup => self.level.pickups.retain(|&spot| spot != tile),
+        }
+    }
+
+    fn save(&mut self) {
+        if let Err(err) = self.level.validate() {
+            self.set_status(format!("not saved: {}", err));
+            return;
+        }
+        match self.level.save(&self.path) {
+            Ok(()) => self.set_status(format!("saved {}", self.path.display())),
+            Err(err) => self.set_status(format!("cannot write {}: {}", self.path.display(), err)),
+        }
+    }
+
+    fn reload(&mut self) {
+        match Level::load(&self.path) {
+            Ok(level) => {
+                self.level = level;
+                self.slot = 0;
+                self.set_status(format!("loaded {}", self.path.display()));
+            }
+            Err(err) => self.set_status(err.to_string()),
+        }
+    }
+
+    // empty map of the same size, framed by a border; spawns and spots are kept
+    fn clear(&mut self) {
+        let (w, h) = (self.level.width as usize, self.level.height as usize);
+        for (y, row) in self.level.tiles.iter_mut().enumerate() {
+            for (x, tile) in row.iter_mut().enumerate() {
+                let edge = x == 0 || y == 0 || x == w - 1 || y == h - 1;
+                *tile = if edge { WallType::Border } else { WallType::Empty };
+            }
+        }
+        self.set_status("cleared");
+    }
+
+    pub fn draw(&self, d: &mut RaylibDrawHandle) {
+        let (sw, sh) = (d.get_screen_width(), d.get_screen_height());
+        let (origin, tile_px) = self.layout(sw, sh);
+        let (ox, oy) = (origin.x as i32, origin.y as i32);
+
+        d.clear_background(Color::BLACK);
+
+        for (y, row) in self.level.tiles.iter().enumerate() {
+            for (x, &tile) in row.iter().enumerate() {
+                let (px, py) = (ox + x as i32 * tile_px, oy + y as i32 * tile_px);
+                let color = tile_color(tile);
+                if color.a > 0 {
+                    d.draw_rectangle(px, py, tile_px, tile_px, color);
+                }
+                d.draw_rectangle_lines(px, py, tile_px, tile_px, Color::new(255, 255, 255, 20));
+            }
+        }
+
+        for &[x, y] in &self.level.pickups {
+            let inset = tile_px / 4;
+            d.draw_rectangle_lines(
+                ox + x * tile_px + inset,
+                oy + y * tile_px + inset,
+                tile_px - inset * 2,
+                tile_px - inset * 2,
+                Color::SKYBLUE,
+            );
+        }
+
+        for (slot, spawn) in self.level.spawns.iter().enumerate() {
+            let [x, y] = spawn.position;
+            let (cx, cy) = (ox + x * tile_px + tile_px / 2, oy + y * tile_px + tile_px / 2);
+            let [dx, dy] = spawn.facing.position_from(&[0, 0]);
+            let color = SPAWN_COLORS[slot % MAX_SPAWNS];
+
+            d.draw_circle(cx, cy, tile_px as f32 * 0.4, color);
+            d.draw_line(cx, cy, cx + dx * tile_px / 2, cy + dy * tile_px / 2, Color::WHITE);
+            if slot == self.slot {
+                d.draw_circle_lines(cx, cy, tile_px as f32 * 0.55, Color::WHITE);
+            }
+        }
+
+        self.draw_panel(d, sw - PANEL_WIDTH, MARGIN);
+    }
+
+    fn draw_panel(&self, d: &mut RaylibDrawHandle, x: i32, y: i32) {
+        let name = if self.level.name.is_empty() { "untitled" } else { &self.level.name };
+        let mut lines = vec![
+            (format!("EDITOR - {}", name), Color::YELLOW),
+            (format!("{}x{}  {}", self.level.width, self.level.height, self.path.display()), Color::GRAY),
+            (String::new(), Color::WHITE),
+            (format!("brush: {}", self.brush.label()), Color::GREEN),
+            (
+                if self.slot < self.level.spawns.len() {
+                    format!("spawn slot: {}", self.slot + 1)
+                } else {
+                    format!("spawn slot: {} (new)", self.slot + 1)
+                },
+                Color::GREEN,
+            ),
+            (String::new(), Color::WHITE),
+        ];
+        lines.extend(
+            [
+                "1-5  Empty Brick Concrete Net Border",
+                "8-0  Water Ice Forest",
+                "6    spawns, 7 pickup spots",
+                "LMB  paint    RMB  erase",
+                "TAB  next spawn slot",
+                "F    turn spawn",
+                "F5   test-play",
+                "F2   back to menu",
+                "Ctrl+S save  Ctrl+L reload",
+                "Ctrl+N clear",
+            ]
+            .iter()
+            .map(|line| (line.to_string(), Color::RAYWHITE)),
+        );
+
+        let mut line_y = y;
+        for (line, color) in &lines {
+            d.draw_text(line, x, line_y, 14, *color);
+            line_y += 20;
+        }
+
+        if self.status_ttl > 0.0 {
+            d.draw_text(&self.status, MARGIN, d.get_screen_height() - 18, 14, Color::ORANGE);
+        }
+    }
+}
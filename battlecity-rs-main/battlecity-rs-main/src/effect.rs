@@ -0,0 +1,198 @@
+//! Timed status effects on a tank, e.g. a helmet's invulnerability or a
+//! clock's freeze. Each kind is either on or off with a time left; applying
+//! one that is already running follows its `Stacking` rule.
+
+/// Longest any effect can be stacked up to, seconds.
+pub const MAX_EFFECT_TIME: f64 = 30.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatusEffectKind {
+    /// Bullets and grenades do nothing.
+    Invulnerable,
+    /// Cannot move or shoot.
+    Frozen,
+    /// Still appearing at its spawn: not drawn, cannot move or shoot.
+    Spawning,
+    /// Drives two tiles per tick.
+    SpeedBoost,
+    /// Reloads twice as fast.
+    RapidFire,
+    /// Bullets hit one point harder.
+    DamageBoost,
+}
+
+/// What applying an effect that is already running does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stacking {
+    /// The longer of the time left and the new duration wins.
+    Refresh,
+    /// The new duration is added on top, up to `MAX_EFFECT_TIME`.
+    Extend,
+}
+
+impl StatusEffectKind {
+    pub const ALL: [StatusEffectKind; 6] = [
+        StatusEffectKind::Invulnerable,
+        StatusEffectKind::Frozen,
+        StatusEffectKind::Spawning,
+        StatusEffectKind::SpeedBoost,
+        StatusEffectKind::RapidFire,
+        StatusEffectKind::DamageBoost,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusEffectKind::Invulnerable => "Invulnerable",
+            StatusEffectKind::Frozen => "Frozen",
+            StatusEffectKind::Spawning => "Spawning",
+            StatusEffectKind::SpeedBoost => "Speed",
+            StatusEffectKind::RapidFire => "Rapid fire",
+            StatusEffectKind::DamageBoost => "Damage",
+        }
+    }
+
+    // protection and freezes do not pile up, boosts do
+    pub fn stacking(self) -> Stacking {
+        match self {
+            StatusEffectKind::Invulnerable | StatusEffectKind::Frozen | StatusEffectKind::Spawning => {
+                Stacking::Refresh
+            }
+            StatusEffectKind::SpeedBoost | StatusEffectKind::RapidFire | StatusEffectKind::DamageBoost => {
+                Stacking::Extend
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    /// Seconds left.
+    pub remaining: f64,
+}
+
+/// Effects running on one tank, at most one per kind.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Starts `kind` for `seconds`, or stacks it onto the running one.
+    pub fn apply(&mut self, kind: StatusEffectKind, seconds: f64) {
+        if seconds <= 0.0 { return; }
+
+        match self.effects.iter_mut().find(|effect| effect.kind == kind) {
+            Some(effect) => {
+                effect.remaining = match kind.stacking() {
+                    Stacking::Refresh => effect.remaining.max(seconds),
+                    Stacking::Extend => (effect.remaining + seconds).min(MAX_EFFECT_TIME),
+                };
+            }
+            None => self.effects.push(StatusEffect { kind, remaining: seconds.min(MAX_EFFECT_TIME) }),
+        }
+    }
+
+    pub fn remove(&mut self, kind: StatusEffectKind) {
+        self.effects.retain(|effect| effect.kind != kind);
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    pub fn has(&self, kind: StatusEffectKind) -> bool {
+        self.remaining(kind) > 0.0
+    }
+
+    /// Seconds left of `kind`, 0 when it is not running.
+    pub fn remaining(&self, kind: StatusEffectKind) -> f64 {
+        self.effects.iter().find(|effect| effect.kind == kind).map_or(0.0, |effect| effect.remaining)
+    }
+
+    /// Running effects, in the order they started.
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+
+    /// Counts every effect down; returns the kinds that ran out.
+    pub fn on_frame(&mut self, dt: f64) -> Vec<StatusEffectKind> {
+        let mut expired = vec![];
+        self.effects.retain_mut(|effect| {
+            effect.remaining -= dt;
+            if effect.remaining > 0.0 { return true; }
+            expired.push(effect.kind);
+            false
+        });
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_keeps_the_longer_time() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffectKind::Invulnerable, 3.0);
+        effects.apply(StatusEffectKind::Invulnerable, 1.0);
+        assert_eq!(effects.remaining(StatusEffectKind::Invulnerable), 3.0);
+        effects.apply(StatusEffectKind::Invulnerable, 5.0);
+        assert_eq!(effects.remaining(StatusEffectKind::Invulnerable), 5.0);
+        assert_eq!(effects.iter().count(), 1);
+    }
+
+    #[test]
+    fn extend_adds_up_to_the_cap() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffectKind::RapidFire, 10.0);
+        effects.apply(StatusEffectKind::RapidFire, 5.0);
+        assert_eq!(effects.remaining(StatusEffectKind::RapidFire), 15.0);
+        effects.apply(StatusEffectKind::RapidFire, 25.0);
+        assert_eq!(effects.remaining(StatusEffectKind::RapidFire), MAX_EFFECT_TIME);
+    }
+
+    #[test]
+    fn a_new_effect_is_capped_too() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffectKind::Frozen, MAX_EFFECT_TIME + 10.0);
+        assert_eq!(effects.remaining(StatusEffectKind::Frozen), MAX_EFFECT_TIME);
+    }
+
+    #[test]
+    fn nothing_is_applied_for_no_time() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffectKind::SpeedBoost, 0.0);
+        assert!(!effects.has(StatusEffectKind::SpeedBoost));
+        assert_eq!(effects.iter().count(), 0);
+    }
+
+    #[test]
+    fn on_frame_returns_what_ran_out() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffectKind::Spawning, 1.0);
+        effects.apply(StatusEffectKind::DamageBoost, 2.0);
+
+        assert!(effects.on_frame(0.5).is_empty());
+        assert_eq!(effects.on_frame(0.5), vec![StatusEffectKind::Spawning]);
+        assert!(!effects.has(StatusEffectKind::Spawning));
+        assert!(effects.has(StatusEffectKind::DamageBoost));
+        assert_eq!(effects.on_frame(1.0), vec![StatusEffectKind::DamageBoost]);
+        assert_eq!(effects.iter().count(), 0);
+    }
+
+    #[test]
+    fn every_kind_stacks_as_documented() {
+        for kind in StatusEffectKind::ALL {
+            let mut effects = StatusEffects::default();
+            effects.apply(kind, 2.0);
+            effects.apply(kind, 1.0);
+            let expected = match kind.stacking() {
+                Stacking::Refresh => 2.0,
+                Stacking::Extend => 3.0,
+            };
+            assert_eq!(effects.remaining(kind), expected, "{}", kind.name());
+        }
+    }
+}
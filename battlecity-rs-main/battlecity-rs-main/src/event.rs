@@ -0,0 +1,59 @@
+use crate::{
+    effect::StatusEffectKind, game::GameState, pickup::PickupType, transform::LookDirection,
+    wall::WallType,
+};
+
+/// Something that happened inside the simulation. `Game` queues these while it
+/// ticks and frontends drain them once per frame (`Game::drain_events`) to play
+/// sounds, feed the HUD, collect stats or sync peers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    BulletFired {
+        owner: u32,
+        position: [i32; 2],
+        direction: LookDirection,
+    },
+    /// Every hit that did damage, lethal or not; a lethal one is followed by
+    /// `TankDestroyed`. Hits on an invulnerable tank are not reported.
+    TankHit {
+        attacker: u32,
+        victim: u32,
+        position: [i32; 2],
+    },
+    TankDestroyed {
+        killer: u32,
+        victim: u32,
+        position: [i32; 2],
+    },
+    /// A bullet took hit points off a wall; `hit_points` is what is left of
+    /// it, 0 once destroyed. Hits that do no damage are not reported.
+    WallDamaged {
+        position: [i32; 2],
+        before: WallType,
+        after: WallType,
+        hit_points: u32,
+    },
+    /// A shot wall grew back (`GameRules::wall_regen_time`).
+    WallRestored {
+        position: [i32; 2],
+        wall: WallType,
+    },
+    PickupCollected {
+        player: u32,
+        pickup: PickupType,
+        position: [i32; 2],
+    },
+    /// A timed status effect ran out.
+    EffectEnded {
+        player: u32,
+        effect: StatusEffectKind,
+    },
+    PlayerRespawned {
+        player: u32,
+        position: [i32; 2],
+    },
+    StateChanged {
+        from: GameState,
+        to: GameState,
+    },
+}
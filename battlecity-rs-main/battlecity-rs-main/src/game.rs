@@ -1,115 +1,102 @@
 
 use crate::render::GameRenderObject; 
-use raylib::prelude::{KeyboardKey, RaylibHandle};
-use piston::Key;
 use crate::camera::GameCamera;
-use crate::transform::tile_to_world;
+use glam::Vec3;
 
-use crate::constants::{COLUMN_COUNT, ROW_COUNT};
 
 const EYE_HEIGHT_FP: f32 = 0.5;   
 const NOSE_OFFSET:  f32 = -0.35; 
 const LOOK_DIST:    f32 = 6.0;  
 const CAM_SMOOTH:   f32 = 0.20;  
 
+// most ticks a single frame may run before the backlog is dropped
+const MAX_CATCH_UP_TICKS: u32 = 10;
+
 
 use crate::{
     animation::Animation,
+    bot::{BotBrain, BotConfig, BotPersonality},
+    connectivity::{self, ConnectivityReport},
     constants::*,
+    effect::StatusEffectKind,
+    event::GameEvent,
+    input::{InputAction, PlayerCommand},
+    level::{Level, SpawnPoint},
     pickup::{Pickup, PickupSpawnSystem, PickupType},
     player::Player,
     projectile::Projectile,
+    rules::GameRules,
+    rng::{random_seed, rng_from_seed, GameRng},
     transform::LookDirection,
-    wall::{generate_walls, Wall, WallType},
+    mapgen::MapGeneratorKind,
+    nav::{self, Path},
+    wall::{Wall, WallType},
 
 };
+use rand::Rng;
 
 fn is_in_bounds(x: i32, y: i32, column_count: u8, row_count: u8) -> bool {
     x >= 0 && x < column_count as i32 && y >= 0 && y < row_count as i32
 }
 
+// concrete a shovel put around a spawn, and what was there before
+struct Fortification {
+    owner: u32,
+    remaining: f64,
+    tiles: Vec<([i32; 2], Wall)>,
+}
+
 pub struct Game {
+    rules: GameRules,
     column_count: u8,
     row_count: u8,
     players: Vec<Player>,
     walls: Vec<Vec<Wall>>,
+    // bumped whenever a wall appears or goes away, so bots know to replan
+    walls_revision: u64,
     pickups: Vec<Pickup>,
-    pickup_spawn_systems: [PickupSpawnSystem; 2],
+    pickup_spawn_systems: Vec<PickupSpawnSystem>,
     max_pickups: usize,
     bullets: Vec<Projectile>,
+    fortifications: Vec<Fortification>,
     animations: Vec<Animation>,
-    accumulated_time: f64,
-    last_update: f64,
+    events: Vec<GameEvent>,
+    tick_accumulator: f64,
+    tick_count: u64,
     update_interval: f64,
     camera: GameCamera,
 
+    // every random decision goes through this, never the thread rng
+    seed: u64,
+    rng: GameRng,
+
     // botsito
     bot_brains: Vec<Option<BotBrain>>,
 
    
     state: GameState,   
     state_time: f32,    
+    round: u32,
+    round_wins: Vec<u32>,
+    round_winner: Option<u32>,
+
+    level: Option<Level>,
 }
 
 
 impl Game {
     
-    pub fn new(column_count: u8, row_count: u8) -> Game {
-        let players = vec![
-            Player::new(
-                0,
-                [0, 0],
-                LookDirection::Down,
-                [Key::Up, Key::Right, Key::Down, Key::Left],
-                Key::Space,
-            )
-            .set_tiles(TANK_1_TILES),
-            Player::new(
-                1,
-                [column_count as i32 - 1, row_count as i32 - 1],
-                LookDirection::Up,
-                [Key::W, Key::D, Key::S, Key::A],
-                Key::X,
-            )
-            .set_tiles(TANK_3_TILES),
-            Player::new(
-                2,
-                [0, row_count as i32 - 1],
-                LookDirection::Up,
-                [Key::T, Key::H, Key::G, Key::F],
-                Key::B,
-            )
-            .set_tiles(TANK_2_TILES),
-            Player::new(
-                3,
-                [column_count as i32 - 1, 0],
-                LookDirection::Down,
-                [Key::I, Key::L, Key::K, Key::J],
-                Key::M,
-            )
-            .set_tiles(TANK_4_TILES),
-        ];
+    pub fn new(rules: GameRules, seed: u64) -> Game {
+        let mut rng = rng_from_seed(seed);
+        let (column_count, row_count) = (rules.column_count, rules.row_count);
 
-        let mut walls = generate_walls(column_count, row_count);
+        let spawns = Self::default_spawns(column_count, row_count);
+        let players = Self::build_players(&spawns, &rules);
 
-      
-        for p in &players {
-            let [sx, sy] = p.get_position();
-            if sy >= 0 && (sy as usize) < walls.len()
-                && sx >= 0 && (sx as usize) < walls[0].len()
-            {
-                walls[sy as usize][sx as usize] = crate::wall::Wall::new([sx, sy]).empty();
-            }
-        }
-
-      
-        for p in &players {
-            crate::wall::carve_safe_zone(&mut walls, p.get_position(), 2);
-        }
+        let walls = Self::build_walls(&rules, &players, None, &mut rng);
+        let round_wins = vec![0; players.len()];
 
-        let bot_brains = (0..players.len())
-        .map(|i| if i == 0 { None } else { Some(BotBrain::default()) })
-        .collect::<Vec<_>>();
+        let bot_brains = Self::build_bot_brains(players.len(), &rules);
 
 
 
@@ -121,108 +108,314 @@ impl Game {
             players,
             bot_brains,
             walls,
+            walls_revision: 0,
             
 
-            pickup_spawn_systems: [
-                PickupSpawnSystem::new(PickupType::Armor, ARMOR_SPAWN_TIME),
-                PickupSpawnSystem::new(PickupType::Health, HEALTH_SPAWN_TIME),
-            ],
+            pickup_spawn_systems: PickupType::ALL
+                .iter()
+                .map(|&pickup| PickupSpawnSystem::new(pickup, pickup.spawn_time(&rules)))
+                .collect(),
 
-            max_pickups: MAX_SPAWNED_PICKUPS,
+            max_pickups: rules.max_pickups,
             pickups: vec![],
             bullets: vec![],
+            fortifications: vec![],
             animations: vec![],
-            last_update: 0.0,
-            accumulated_time: 0.0,
-            update_interval: GAME_TICK_INTERVAL,
+            events: vec![],
+            tick_accumulator: 0.0,
+            tick_count: 0,
+            update_interval: rules.tick_interval,
             camera: GameCamera::default(),
+            seed,
+            rng,
 
              state: GameState::Menu,
             state_time: 0.0,
+            round: 0,
+            round_wins,
+            round_winner: None,
+            level: None,
+            rules,
+        }
+    }
 
-            
+    /// Corners of the random map, in slot order.
+    fn default_spawns(column_count: u8, row_count: u8) -> Vec<SpawnPoint> {
+        let (right, bottom) = (column_count as i32 - 1, row_count as i32 - 1);
+        vec![
+            SpawnPoint { position: [0, 0], facing: LookDirection::Down },
+            SpawnPoint { position: [right, bottom], facing: LookDirection::Up },
+            SpawnPoint { position: [0, bottom], facing: LookDirection::Up },
+            SpawnPoint { position: [right, 0], facing: LookDirection::Down },
+        ]
+    }
+
+    fn build_players(spawns: &[SpawnPoint], rules: &GameRules) -> Vec<Player> {
+        const TILES: [[[f64; 4]; 8]; 4] = [TANK_1_TILES, TANK_3_TILES, TANK_2_TILES, TANK_4_TILES];
+
+        spawns
+            .iter()
+            .zip(TILES)
+            .enumerate()
+            .map(|(id, (spawn, tiles))| {
+                Player::new(id as u32, spawn.position, spawn.facing, rules).set_tiles(tiles)
+            })
+            .collect()
+    }
+
+    // the first `rules.humans` slots are people, every other one a bot set up from the rules
+    fn build_bot_brains(count: usize, rules: &GameRules) -> Vec<Option<BotBrain>> {
+        (0..count)
+            .map(|i| (i >= rules.humans).then(|| BotBrain::new(rules.bot(i - rules.humans))))
+            .collect()
+    }
+
+    fn build_walls(
+        rules: &GameRules,
+        players: &[Player],
+        level: Option<&Level>,
+        rng: &mut GameRng,
+    ) -> Vec<Vec<Wall>> {
+        // authored maps are played exactly as drawn
+        if let Some(level) = level {
+            return level.walls();
+        }
+
+        let mut walls = rules.map_generator.generator().generate(rules, rng);
+
+      
+        for p in players {
+            let [sx, sy] = p.get_position();
+            if sy >= 0 && (sy as usize) < walls.len()
+                && sx >= 0 && (sx as usize) < walls[0].len()
+            {
+                walls[sy as usize][sx as usize] = crate::wall::Wall::new([sx, sy]).empty();
+            }
         }
+
+      
+        for p in players {
+            crate::wall::carve_safe_zone(&mut walls, p.get_position(), 2);
+        }
+
+        // concrete may still seal a spawn off, cut a way through it
+        let spawns = players.iter().map(|p| p.get_spawn().0).collect::<Vec<_>>();
+        connectivity::repair(&mut walls, &spawns);
+
+        walls
     }
 
 
     pub fn new_default() -> Game {
-        Game::new(COLUMN_COUNT, ROW_COUNT)
+        Game::new(GameRules::default(), random_seed())
     }
 
-   pub fn poll_input_raylib(&mut self, rl: &raylib::prelude::RaylibHandle) {
-        use raylib::prelude::KeyboardKey;
+    /// Switches the generator for random maps and builds a new one; back to the menu.
+    pub fn set_map_generator(&mut self, kind: MapGeneratorKind) {
+        self.rules.map_generator = kind;
+        self.reset();
+    }
 
-        fn map_key(k: piston::Key) -> KeyboardKey {
-            use piston::Key::*;
-            use KeyboardKey::*;
-            match k {
-                Up => KEY_UP, Right => KEY_RIGHT, Down => KEY_DOWN, Left => KEY_LEFT,
-                W => KEY_W, A => KEY_A, S => KEY_S, D => KEY_D,
-                T => KEY_T, H => KEY_H, G => KEY_G, F => KEY_F,
-                I => KEY_I, L => KEY_L, K => KEY_K, J => KEY_J,
-                X => KEY_X, B => KEY_B, M => KEY_M,
-                Space => KEY_SPACE,
-                _ => KEY_NULL,
-            }
+    /// Plays `level` instead of random maps from now on: its size replaces the
+    /// rules' map size, one tank is placed per spawn and the match goes back
+    /// to the menu. The first `GameRules::humans` slots are people, the rest bots.
+    pub fn load_level(&mut self, level: Level) {
+        self.column_count = level.width;
+        self.row_count = level.height;
+
+        self.players = Self::build_players(&level.spawns, &self.rules);
+        self.bot_brains = Self::build_bot_brains(self.players.len(), &self.rules);
+        self.round_wins = vec![0; self.players.len()];
+
+        self.level = Some(level);
+        self.reset();
+    }
+
+    /// How the tank at `index` is played by the computer, `None` for humans.
+    pub fn bot_config(&self, index: usize) -> Option<BotConfig> {
+        self.bot_brains.get(index)?.as_ref().map(|brain| brain.config)
+    }
+
+    /// Changes how the bot at `index` plays, from its next decision on; kept
+    /// for later matches too. Human slots stay human.
+    pub fn set_bot_config(&mut self, index: usize, config: BotConfig) {
+        let Some(Some(brain)) = self.bot_brains.get_mut(index) else { return };
+        brain.config = config;
+        if let Some(n) = index.checked_sub(self.rules.humans) {
+            self.rules.set_bot(n, config);
         }
+    }
 
-        if let Some(p) = self.players.get_mut(0) {
-            for i in 0..4 {
-                let key = map_key(p.movement_key(i));
-                p.set_move_pressed(i, key != KeyboardKey::KEY_NULL && rl.is_key_down(key));
-            }
-            let fire_key = map_key(p.fire_key());
-            p.set_fire_pressed(fire_key != KeyboardKey::KEY_NULL && rl.is_key_down(fire_key));
+    /// The first bot slot after `selected`, wrapping around, so a menu can
+    /// step through the bots; `None` when every tank is human.
+    pub fn next_bot_slot(&self, selected: usize) -> Option<usize> {
+        let count = self.bot_brains.len();
+        (1..=count)
+            .map(|step| (selected + step) % count)
+            .find(|&index| self.bot_brains[index].is_some())
+    }
+
+    /// Hands the first `humans` tanks to people and the rest to the bots, 0
+    /// for a match of bots only; back to the menu.
+    pub fn set_humans(&mut self, humans: usize) {
+        let humans = humans.min(self.players.len());
+        // every bot keeps its settings, wherever the bot slots now start
+        let configs = (0..self.players.len())
+            .map(|index| self.bot_config(index).unwrap_or_default())
+            .collect::<Vec<_>>();
+        self.rules.humans = humans;
+        self.rules.bots = configs.into_iter().skip(humans).collect();
+        self.bot_brains = Self::build_bot_brains(self.players.len(), &self.rules);
+        self.reset();
+    }
+
+    /// The authored map being played, `None` for random maps.
+    pub fn level(&self) -> Option<&Level> {
+        self.level.as_ref()
+    }
+
+    /// Routes between the spawns on the map as it stands now.
+    pub fn connectivity(&self) -> ConnectivityReport {
+        let spawns = self.players.iter().map(|p| p.get_spawn().0).collect::<Vec<_>>();
+        connectivity::check(&self.walls, &spawns)
+    }
+
+    /// The map as it stands now (walls shot away included) with the current
+    /// spawns, e.g. to save a random map or open it in the editor.
+    pub fn current_level(&self) -> Level {
+        let spawns = self
+            .players
+            .iter()
+            .map(|player| {
+                let (position, facing) = player.get_spawn();
+                SpawnPoint { position, facing }
+            })
+            .collect();
+
+        Level {
+            name: self.level.as_ref().map_or_else(|| format!("Seed {}", self.seed), |l| l.name.clone()),
+            width: self.column_count,
+            height: self.row_count,
+            tiles: self.walls.iter().map(|row| row.iter().map(|w| w.variant()).collect()).collect(),
+            spawns,
+            pickups: self.level.as_ref().map_or_else(Vec::new, |l| l.pickups.clone()),
         }
     }
 
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
 
+    /// Seed the match was created (or last reseeded) with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
 
+    /// Routes a command to its player; commands for unknown ids are dropped.
+    pub fn apply_command(&mut self, command: PlayerCommand) {
+        if let Some(player) = self.players.iter_mut().find(|p| p.get_id() == command.player_id) {
+            player.set_action(command.action, command.pressed);
+        }
+    }
+
+    /// Per-frame entry point. Presentation (animations, camera) follows the
+    /// frame time, the simulation itself only advances in whole `tick`s, as
+    /// many as the elapsed time owes, so slow frames never drop game time.
     pub fn update_dt(&mut self, dt: f32) {
-        // advance timers
-        self.accumulated_time += dt as f64;
+        self.tick_accumulator += dt as f64;
+
+        let mut ticks = 0;
+        while self.tick_accumulator >= self.update_interval {
+            self.tick_accumulator -= self.update_interval;
+            self.tick();
+
+            ticks += 1;
+            if ticks == MAX_CATCH_UP_TICKS {
+                // too far behind (debugger, suspended window): give up on the backlog
+                self.tick_accumulator = 0.0;
+                break;
+            }
+        }
 
-      
-        self.animations.retain_mut(|animation| {
-            animation.on_frame(dt);
-            !animation.is_finished()
-        });
+        if self.state != GameState::Paused {
+            self.animations.retain_mut(|animation| {
+                animation.on_frame(dt);
+                !animation.is_finished()
+            });
+        }
+
+        self.update_camera();
+    }
+
+    /// Advances the simulation by exactly one step of `GameRules::tick_interval`.
+    /// Only `Playing` moves the world; the other phases just run their timers.
+    pub fn tick(&mut self) {
+        let dt = self.update_interval;
+        self.tick_count += 1;
+
+        match self.state {
+            GameState::Menu | GameState::Paused | GameState::MatchOver => return,
+            GameState::Countdown => {
+                self.state_time += dt as f32;
+                if self.state_time as f64 >= self.rules.countdown_duration {
+                    self.set_state(GameState::Playing);
+                }
+                return;
+            }
+            GameState::RoundOver => {
+                self.state_time += dt as f32;
+                if self.state_time as f64 >= self.rules.round_over_duration {
+                    self.finish_round();
+                }
+                return;
+            }
+            GameState::Playing => self.state_time += dt as f32,
+        }
 
-       
         for player in &mut self.players {
-            player.on_frame(dt as f64);
+            for effect in player.on_frame(dt) {
+                let id = player.get_id();
+                // the sparkle lasts exactly as long as the tank is spawning
+                if effect == StatusEffectKind::Spawning {
+                    self.animations.retain(|animation| animation.get_player() != Some(id));
+                }
+                self.events.push(GameEvent::EffectEnded { player: id, effect });
+            }
         }
 
-        // 
-        self.think_bots(dt as f64);
+        self.think_bots(dt);
 
-        // 
         for system in &mut self.pickup_spawn_systems {
-            system.on_frame(dt as f64);
+            system.on_frame(dt);
 
             if self.pickups.len() >= self.max_pickups {
                 continue;
             }
 
             if let Some(mut pickup) = system.get_pickup_to_spawn() {
-                let empty_positions = self
-                    .walls
-                    .iter()
-                    .enumerate()
-                    .flat_map(|(y, row)| {
-                        row.iter().enumerate().filter_map(move |(x, wall)| {
-                            if wall.variant() == WallType::Empty {
-                                Some([x as i32, y as i32])
-                            } else {
-                                None
-                            }
+                let is_free = |[x, y]: [i32; 2]| {
+                    is_in_bounds(x, y, self.column_count, self.row_count)
+                        && self.walls[y as usize][x as usize].variant() == WallType::Empty
+                        && self.pickups.iter().all(|p| *p.get_position() != [x, y])
+                };
+
+                // a level may pin pickups to a few spots, otherwise any empty tile
+                let empty_positions = match self.level.as_ref().filter(|l| !l.pickups.is_empty()) {
+                    Some(level) => level.pickups.iter().copied().filter(|&p| is_free(p)).collect(),
+                    None => self
+                        .walls
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(y, row)| {
+                            (0..row.len()).map(move |x| [x as i32, y as i32])
                         })
-                    })
-                    .collect::<Vec<[i32; 2]>>();
+                        .filter(|&p| is_free(p))
+                        .collect::<Vec<[i32; 2]>>(),
+                };
 
                 if !empty_positions.is_empty() {
                     let spawn_position =
-                        empty_positions[rand::random::<usize>() % empty_positions.len()];
+                        empty_positions[self.rng.gen_range(0..empty_positions.len())];
                     pickup.set_position(spawn_position);
                     self.pickups.push(pickup);
                     system.reset_spawn_timer();
@@ -230,165 +423,392 @@ impl Game {
             }
         }
 
-        // 
-        if self.accumulated_time - self.last_update < self.update_interval {
-            return;
-        }
-        self.last_update = self.accumulated_time;
-
-        
         for i in 0..self.players.len() {
             if !self.players[i].get_is_alive() {
                 continue;
             }
 
-            
+            // standing still this tick: previous == current, nothing to interpolate
             let position = self.players[i].get_position();
             self.players[i].set_position(position);
 
-            if let Some(direction) = self.players[i].get_pressed_direction() {
-                let position = self.players[i].get_position();
-                let new_position = direction.position_from(&position);
-                let [x, y] = new_position;
-
-                let is_intersecting = !is_in_bounds(x, y, self.column_count, self.row_count)
-                    || self.walls[y as usize][x as usize].is_solid()
-                    || self.players[..]
-                        .iter()
-                        .enumerate()
-                        .filter(|(index, p)| *index != i && p.get_is_alive())
-                        .any(|(_, p)| p.get_position() == new_position);
+            if !self.players[i].can_act() {
+                continue;
+            }
 
-                if !is_intersecting {
-                    self.players[i].set_position([x, y]);
+            // a speed boost drives two single-tile steps
+            for _ in 0..self.players[i].get_move_speed() {
+                // a tank that just drove onto ice goes one more tile, whatever is pressed
+                let sliding = self.players[i].take_sliding();
+                if let Some(direction) = sliding.or_else(|| self.players[i].get_pressed_direction()) {
+                    let position = self.players[i].get_position();
+                    let new_position = direction.position_from(&position);
+                    let [x, y] = new_position;
+
+                    let is_intersecting = !is_in_bounds(x, y, self.column_count, self.row_count)
+                        || self.walls[y as usize][x as usize].blocks_tanks()
+                        || self.players[..]
+                            .iter()
+                            .enumerate()
+                            .filter(|(index, p)| *index != i && p.get_is_alive())
+                            .any(|(_, p)| p.get_position() == new_position);
+
+                    if !is_intersecting {
+                        self.players[i].set_position([x, y]);
+
+                        if sliding.is_none() && self.walls[y as usize][x as usize].is_slippery() {
+                            self.players[i].set_sliding(Some(direction));
+                        }
+                    }
+
+                    self.players[i].set_direction(direction);
                 }
-
-                self.players[i].set_direction(direction);
             }
 
             if self.players[i].get_is_fire_pressed() && self.players[i].shoot() {
                 let position = self.players[i].get_position();
-                let direction = self.players[i].get_direction();
-                let bullet = Projectile::new(self.players[i].get_id(), position, *direction);
+                let direction = *self.players[i].get_direction();
+                let owner = self.players[i].get_id();
+                let bullet = Projectile::new(owner, position, direction)
+                    .with_power(self.players[i].get_shot_power())
+                    .with_speed(self.players[i].get_shot_speed());
                 self.bullets.push(bullet);
+                self.events.push(GameEvent::BulletFired { owner, position, direction });
             }
         }
 
         // respawns
-        self.players
-            .iter_mut()
-            .filter(|player| {
-                !player.get_is_alive() && player.get_is_fire_pressed() && player.can_respawn()
-            })
-            .for_each(|player| {
-                player.respawn();
-                self.animations
-                    .push(Animation::new_spawn(player.get_position()));
-            });
+        for i in 0..self.players.len() {
+            let player = &self.players[i];
+            let waiting_for_fire = self.rules.respawn_requires_fire && !player.get_is_fire_pressed();
+            if player.get_is_alive()
+                || !player.can_respawn()
+                || player.get_respawn_countdown() > 0.0
+                || waiting_for_fire
+            {
+                continue;
+            }
+            // every spawn taken: try again next tick
+            let Some((position, facing)) = self.safest_spawn(i) else { continue };
+
+            self.players[i].respawn_at(position, facing);
+            self.protect_spawn(i, SPAWN_ANIMATION_TIME);
+            self.events.push(GameEvent::PlayerRespawned { player: self.players[i].get_id(), position });
+        }
 
        
+        let mut collected = vec![];
         self.pickups.retain(|pickup| {
-            let position = pickup.get_position();
-            let mut players_to_pickup = self
+            let position = *pickup.get_position();
+            let collector = self
                 .players
-                .iter_mut()
-                .filter(|player| player.get_is_alive() && player.get_position() == *position);
-
-            let is_picked_up = players_to_pickup.any(|player| match pickup.get_variant() {
-                PickupType::Armor => player.add_armor(),
-                PickupType::Health => player.add_health(),
-            });
+                .iter()
+                .find(|player| player.get_is_alive() && player.get_position() == position);
 
-            !is_picked_up
+            match collector {
+                Some(player) => {
+                    collected.push((player.get_id(), *pickup.get_variant(), position));
+                    false
+                }
+                None => true,
+            }
         });
+        for (player, pickup, position) in collected {
+            self.apply_pickup(player, pickup);
+            self.events.push(GameEvent::PickupCollected { player, pickup, position });
+        }
 
         self.update_bullets();
+        self.update_fortifications(dt);
+        self.regrow_walls(dt);
+        self.check_round_over();
+    }
 
-        use crate::transform::tile_to_world;
-        use crate::constants::{CAMERA_EYE_HEIGHT, CAMERA_EYE_Z}; 
-       if let Some(p0) = self.players.get(0) {
-            use glam::Vec3;
-            use crate::camera::GameCamera;
-            use crate::transform::tile_to_world;
-
-           
-            let t = ((self.accumulated_time - self.last_update) / self.update_interval)
-                .clamp(0.0, 1.0) as f32;
-
-           
-            let prev_w = tile_to_world(*p0.get_previous_position());
-            let curr_w = tile_to_world(p0.get_position());
-            let center = prev_w.lerp(curr_w, t);  
+    /// Tiles some bullet will fly through within the next `ticks` ticks, as
+    /// far as walls let it.
+    pub fn bullet_danger(&self, ticks: u32) -> Vec<[i32; 2]> {
+        self.bullets
+            .iter()
+            .flat_map(|bullet| self.bullet_path(bullet, ticks))
+            .map(|(tile, _)| tile)
+            .collect()
+    }
 
-            let fwd_ld  = *p0.get_direction();
-            let forward = GameCamera::forward_from(fwd_ld);
+    /// Tiles `bullet` will fly through within the next `ticks` ticks, each
+    /// with the tick (1 = the next one) it gets there, up to the first wall.
+    pub fn bullet_path(&self, bullet: &Projectile, ticks: u32) -> Vec<([i32; 2], u32)> {
+        let mut tiles = vec![];
+        let mut position = *bullet.get_position();
+        for step in 0..ticks * bullet.get_speed() {
+            position = bullet.get_direction().position_from(&position);
+            let [x, y] = position;
+            if !is_in_bounds(x, y, self.column_count, self.row_count)
+                || self.walls[y as usize][x as usize].blocks_bullets()
+            {
+                break;
+            }
+            tiles.push((position, step / bullet.get_speed() + 1));
+        }
+        tiles
+    }
 
-            
-            let next_tile = fwd_ld.position_from(&p0.get_position());
-            let ahead_solid = !is_in_bounds(next_tile[0], next_tile[1], self.column_count, self.row_count)
-                || self.walls[next_tile[1] as usize][next_tile[0] as usize].is_solid();
-            let nose = if ahead_solid { 0.0 } else { NOSE_OFFSET };
+    // what bullets that can hurt tank `index` fly through within `ticks` ticks:
+    // tile, tick it gets there, and which way it flies
+    fn bullet_threats(&self, index: usize, ticks: u32) -> Vec<([i32; 2], u32, LookDirection)> {
+        let me = &self.players[index];
+        self.bullets
+            .iter()
+            .filter(|bullet| {
+                !self.players.iter().any(|p| p.get_id() == bullet.get_owner_id() && p.is_ally_of(me))
+            })
+            .flat_map(|bullet| {
+                let direction = *bullet.get_direction();
+                self.bullet_path(bullet, ticks).into_iter().map(move |(tile, tick)| (tile, tick, direction))
+            })
+            .collect()
+    }
 
-            
-            let eye_target    = center + Vec3::new(0.0, EYE_HEIGHT_FP, 0.0) + forward * nose;
-            let look_target   = eye_target + forward * LOOK_DIST;
+    /// Where the tank at `index` should come back: the spawn point farthest
+    /// from every living enemy among those nobody stands on and no bullet is
+    /// about to cross. Teammates only count as in the way, not as danger.
+    /// Bullets are only a tie-breaker once every free spawn is in one's way;
+    /// `None` while all of them are taken.
+    fn safest_spawn(&self, index: usize) -> Option<([i32; 2], LookDirection)> {
+        let danger = self.bullet_danger(SPAWN_BULLET_LOOKAHEAD);
+        let me = &self.players[index];
+        let others = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|&(j, p)| j != index && p.get_is_alive())
+            .map(|(_, p)| p)
+            .collect::<Vec<_>>();
+        let taken = others.iter().map(|p| p.get_position()).collect::<Vec<_>>();
+        let enemies = others
+            .iter()
+            .filter(|p| !p.is_ally_of(me))
+            .map(|p| p.get_position())
+            .collect::<Vec<_>>();
+
+        // own spawn first, so it wins ties
+        let own = me.get_spawn();
+        let candidates = std::iter::once(own)
+            .chain(self.players.iter().map(|p| p.get_spawn()))
+            .filter(|&(position, _)| !taken.contains(&position));
+
+        candidates
+            .map(|(position, facing)| {
+                let nearest_enemy = enemies
+                    .iter()
+                    .map(|e| (e[0] - position[0]).abs() + (e[1] - position[1]).abs())
+                    .min()
+                    .unwrap_or(i32::MAX);
+                let safe = !danger.contains(&position);
+                ((safe, nearest_enemy), (position, facing))
+            })
+            .reduce(|best, next| if next.0 > best.0 { next } else { best })
+            .map(|(_, spawn)| spawn)
+    }
 
-            // sm
-            self.camera.approach(eye_target, look_target, CAM_SMOOTH);
-            self.camera.up   = Vec3::Y;
-            self.camera.fovy = 60.0; // 
+    /// Spawn protection, the only place it is handed out: the tank sparkles in
+    /// place for `sparkle` seconds, then stays invulnerable for
+    /// `spawn_protection_time` more.
+    fn protect_spawn(&mut self, index: usize, sparkle: f64) {
+        let player = &mut self.players[index];
+        if sparkle > 0.0 {
+            player.apply_effect(StatusEffectKind::Spawning, sparkle);
+            self.animations.push(Animation::new_spawn(player.get_position()).for_player(player.get_id()));
         }
+        player.apply_effect(StatusEffectKind::Invulnerable, sparkle + self.rules.spawn_protection_time);
+    }
 
-        
-        for i in 0..self.players.len() {
-            if !self.players[i].get_is_alive() {
-                continue;
+    fn apply_pickup(&mut self, collector: u32, pickup: PickupType) {
+        let Some(index) = self.players.iter().position(|p| p.get_id() == collector) else { return };
+
+        match pickup {
+            PickupType::Armor => { self.players[index].add_armor(); }
+            PickupType::Health => { self.players[index].add_health(); }
+            PickupType::Star => { self.players[index].add_star(); }
+            PickupType::Tank => { self.players[index].add_life(); }
+            PickupType::Helmet => {
+                self.players[index].apply_effect(StatusEffectKind::Invulnerable, self.rules.helmet_duration);
+            }
+            PickupType::Shovel => self.fortify_spawn(index),
+            PickupType::Clock => {
+                let duration = self.rules.clock_duration;
+                let enemies = (0..self.players.len())
+                    .filter(|&j| !self.players[j].is_ally_of(&self.players[index]))
+                    .collect::<Vec<_>>();
+                for j in enemies {
+                    if self.players[j].get_is_alive() {
+                        self.players[j].apply_effect(StatusEffectKind::Frozen, duration);
+                    }
+                }
+            }
+            PickupType::Grenade => {
+                for victim in 0..self.players.len() {
+                    if self.players[victim].is_ally_of(&self.players[index]) || !self.players[victim].destroy() {
+                        continue;
+                    }
+
+                    let position = self.players[victim].get_position();
+                    let victim = self.players[victim].get_id();
+                    self.players[index].inc_kill_count();
+                    self.animations.push(Animation::new_explosion(position));
+                    self.events.push(GameEvent::TankDestroyed { killer: collector, victim, position });
+                }
             }
+        }
+    }
 
-          
-            let position = self.players[i].get_position();
-            self.players[i].set_position(position);
+    // rings the player's spawn with concrete, leaving the tile it faces open
+    // so a tank spawning there can still drive out
+    fn fortify_spawn(&mut self, index: usize) {
+        let owner = self.players[index].get_id();
+        let duration = self.rules.shovel_duration;
 
-            if let Some(direction) = self.players[i].get_pressed_direction() {
-                let position = self.players[i].get_position();
-                let new_position = direction.position_from(&position);
-                let [x, y] = new_position;
+        // a second shovel only extends the first one
+        if let Some(existing) = self.fortifications.iter_mut().find(|f| f.owner == owner) {
+            existing.remaining = duration;
+            return;
+        }
 
-                let is_intersecting = !is_in_bounds(x, y, self.column_count, self.row_count)
-                    || self.walls[y as usize][x as usize].is_solid()
-                    || self.players[..]
-                        .iter()
-                        .enumerate()
-                        .filter(|(index, p)| *index != i && p.get_is_alive())
-                        .any(|(_, p)| p.get_position() == new_position);
+        let (spawn, facing) = self.players[index].get_spawn();
+        let exit = facing.position_from(&spawn);
+        let mut tiles = vec![];
 
-                if !is_intersecting {
-                    self.players[i].set_position([x, y]);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let [x, y] = [spawn[0] + dx, spawn[1] + dy];
+                if (dx, dy) == (0, 0) || [x, y] == exit || !is_in_bounds(x, y, self.column_count, self.row_count) {
+                    continue;
+                }
+                let occupied = self.players.iter().any(|p| p.get_is_alive() && p.get_position() == [x, y])
+                    || self.bullets.iter().any(|b| *b.get_position() == [x, y])
+                    || self.pickups.iter().any(|p| *p.get_position() == [x, y]);
+                let fortified = self.fortifications.iter().any(|f| f.tiles.iter().any(|(t, _)| *t == [x, y]));
+                let wall = &mut self.walls[y as usize][x as usize];
+                if occupied || fortified || wall.variant() == WallType::Border {
+                    continue;
                 }
 
-                self.players[i].set_direction(direction);
+                // kept whole, so a chipped brick comes back chipped
+                tiles.push(([x, y], std::mem::replace(wall, Wall::new([x, y]).concrete())));
             }
+        }
+        if !tiles.is_empty() {
+            self.walls_revision += 1;
+        }
 
-            
-            if self.players[i].get_is_fire_pressed() && self.players[i].shoot() {
-                let position  = self.players[i].get_position();
-                let direction = self.players[i].get_direction();
-                let bullet    = Projectile::new(self.players[i].get_id(), position, *direction);
-                self.bullets.push(bullet);
+        self.fortifications.push(Fortification { owner, remaining: duration, tiles });
+    }
+
+    // expired shovels give the tiles back as they were, hit points and
+    // regrowth timer included, or empty if a tank is standing where a wall
+    // would come back
+    fn update_fortifications(&mut self, dt: f64) {
+        for fortification in &mut self.fortifications {
+            fortification.remaining -= dt;
+        }
+
+        let (expired, active) = std::mem::take(&mut self.fortifications)
+            .into_iter()
+            .partition::<Vec<_>, _>(|f| f.remaining <= 0.0);
+        self.fortifications = active;
+
+        for (position, original) in expired.into_iter().flat_map(|f| f.tiles) {
+            let [x, y] = position;
+            let occupied = self.players.iter().any(|p| p.get_is_alive() && p.get_position() == position);
+            self.walls[y as usize][x as usize] = if occupied && original.flags().blocks_tanks {
+                Wall::new(position).empty()
+            } else {
+                original
+            };
+            self.walls_revision += 1;
+        }
+    }
+
+    // shot walls come back once they went `wall_regen_time` without a hit,
+    // but never on top of a tank, bullet or pickup
+    fn regrow_walls(&mut self, dt: f64) {
+        let regen_time = self.rules.wall_regen_time;
+        if regen_time <= 0.0 { return; }
+
+        for y in 0..self.walls.len() {
+            for x in 0..self.walls[y].len() {
+                let wall = &mut self.walls[y][x];
+                if !wall.is_damaged() { continue; }
+
+                wall.on_frame(dt);
+                if wall.get_since_hit() < regen_time { continue; }
+
+                let position = [x as i32, y as i32];
+                let occupied = self.players.iter().any(|p| p.get_is_alive() && p.get_position() == position)
+                    || self.bullets.iter().any(|b| *b.get_position() == position)
+                    || self.pickups.iter().any(|p| *p.get_position() == position);
+                if occupied { continue; }
+
+                let wall = &mut self.walls[y][x];
+                wall.restore();
+                self.events.push(GameEvent::WallRestored { position, wall: wall.variant() });
+                self.walls_revision += 1;
             }
         }
+    }
 
+    /// How far the render frame is between the last tick and the next one,
+    /// in [0, 1). Frontends blend previous and current positions with it.
+    pub fn interpolation_alpha(&self) -> f32 {
+        (self.tick_accumulator / self.update_interval).clamp(0.0, 1.0) as f32
+    }
 
+    pub fn tick_count(&self) -> u64 {
+        self.tick_count
+    }
+
+    fn update_camera(&mut self) {
+        let alpha = self.interpolation_alpha();
+        let Some(p0) = self.players.first() else { return; };
+
+        let center = p0.get_interpolated_world(alpha);
+
+        let fwd_ld  = *p0.get_direction();
+        let forward = GameCamera::forward_from(fwd_ld);
+
+        
+        let next_tile = fwd_ld.position_from(&p0.get_position());
+        let ahead_solid = !is_in_bounds(next_tile[0], next_tile[1], self.column_count, self.row_count)
+            || self.walls[next_tile[1] as usize][next_tile[0] as usize].blocks_tanks();
+        let nose = if ahead_solid { 0.0 } else { NOSE_OFFSET };
 
+        
+        let eye_target    = center + Vec3::new(0.0, EYE_HEIGHT_FP, 0.0) + forward * nose;
+        let look_target   = eye_target + forward * LOOK_DIST;
 
+        // sm
+        self.camera.approach(eye_target, look_target, CAM_SMOOTH);
+        self.camera.up   = Vec3::Y;
+        self.camera.fovy = 60.0; // 
     }
 
+    // fast bullets take several one-tile steps per tick, everything else
+    // only moves in the first
     fn update_bullets(&mut self) {
+        let passes = self.bullets.iter().map(|b| b.get_speed()).max().unwrap_or(0);
+        for pass in 0..passes {
+            self.step_bullets(pass);
+        }
+    }
+
+    fn step_bullets(&mut self, pass: u32) {
         let bullets_length = self.bullets.len();
         let mut bullets_to_keep = vec![true; bullets_length];
 
         for i in 0..bullets_length {
             let bullet = &self.bullets[i];
+            if bullet.get_speed() <= pass || !bullets_to_keep[i] {
+                continue;
+            }
             let position = bullet.get_position();
             let new_position = bullet.get_direction().position_from(&position);
             let [x, y] = new_position;
@@ -401,39 +821,65 @@ impl Game {
 
             // walls
             let wall = &mut self.walls[y as usize][x as usize];
-            if wall.is_solid() {
-                wall.damage();
+            if wall.blocks_bullets() {
+                let before = wall.variant();
+                // armor and the border shrug it off, nothing to tell then
+                if wall.damage(bullet.get_power()) {
+                    if wall.variant() != before {
+                        self.walls_revision += 1;
+                    }
+                    self.events.push(GameEvent::WallDamaged {
+                        position: [x, y],
+                        before,
+                        after: wall.variant(),
+                        hit_points: wall.get_hit_points(),
+                    });
+                }
                 self.animations.push(Animation::new_explosion([x, y]));
                 bullets_to_keep[i] = false;
                 continue;
             }
 
-            // players
-            let players_to_damage = self
+            // players; bullets stop on allies without hurting them
+            let attacker = bullet.get_owner_id();
+            let shooter = self.players.iter().position(|p| p.get_id() == attacker);
+            let struck = self
                 .players
-                .iter_mut()
-                .filter(|player| player.get_is_alive() && (player.get_position() == [x, y]));
-
-            let mut is_player_hit = false;
-            let is_player_killed = players_to_damage
-                .map(|player| {
-                    is_player_hit = true;
-                    player.damage()
-                })
-                .take(1)
-                .any(|is_killed| is_killed);
-
-            if is_player_killed {
-                if let Some(killer) = self
-                    .players
-                    .iter_mut()
-                    .find(|p| p.get_id() == bullet.get_owner_id())
-                {
-                    killer.inc_kill_count();
+                .iter()
+                .position(|player| player.get_is_alive() && (player.get_position() == [x, y]));
+            if let (Some(struck), Some(shooter)) = (struck, shooter) {
+                if self.players[struck].is_ally_of(&self.players[shooter]) {
+                    bullets_to_keep[i] = false;
+                    continue;
                 }
             }
 
-            if is_player_hit {
+            if let Some(index) = struck {
+                // a protected tank shrugs it off: nothing to report or avenge
+                if self.players[index].damage() {
+                    let victim = self.players[index].get_id();
+                    self.events.push(GameEvent::TankHit { attacker, victim, position: [x, y] });
+                    if let Some(brain) = self.bot_brains[index].as_mut() {
+                        brain.last_attacker = Some(attacker);
+                        brain.revenge_time = BOT_REVENGE_TIME;
+                    }
+
+                    if !self.players[index].get_is_alive() {
+                        if let Some(killer) = self
+                            .players
+                            .iter_mut()
+                            .find(|p| p.get_id() == attacker)
+                        {
+                            killer.inc_kill_count();
+                        }
+                        self.events.push(GameEvent::TankDestroyed {
+                            killer: attacker,
+                            victim,
+                            position: [x, y],
+                        });
+                    }
+                }
+
                 bullets_to_keep[i] = false;
                 self.animations.push(Animation::new_explosion([x, y]));
                 continue;
@@ -461,28 +907,41 @@ impl Game {
         self.bullets.retain(|_| *keep_iter.next().unwrap());
 
         // 
-        self.bullets.iter_mut().for_each(|bullet| {
+        self.bullets.iter_mut().filter(|bullet| bullet.get_speed() > pass).for_each(|bullet| {
             bullet.set_position(bullet.get_direction().position_from(&bullet.get_position()))
         });
     }
 
+    /// Starts over with a fresh seed, e.g. one shared from another match.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = rng_from_seed(seed);
+        self.reset();
+    }
+
+    /// Back to the menu with a new map and fresh players. The map is the next
+    /// one in this seed's sequence, so reseed first to replay the original layout.
     pub fn reset(&mut self) {
-        self.walls = generate_walls(self.column_count, self.row_count);
+        self.reset_round();
+        self.tick_count = 0;
+        self.round = 0;
+        self.round_wins.iter_mut().for_each(|wins| *wins = 0);
+        self.round_winner = None;
+        self.set_state(GameState::Menu);
+    }
+
+    fn reset_round(&mut self) {
+        self.players.iter_mut().for_each(|player| player.reset());
+        self.walls = Self::build_walls(&self.rules, &self.players, self.level.as_ref(), &mut self.rng);
+        self.walls_revision += 1;
         self.pickups.clear();
         self.bullets.clear();
+        self.fortifications.clear();
         self.animations.clear();
-        self.players.iter_mut().for_each(|player| player.reset());
         self.pickup_spawn_systems
             .iter_mut()
             .for_each(|system| system.reset_spawn_timer());
-        self.accumulated_time = 0.0;
-        self.last_update = 0.0;
-    }
-
-    fn is_game_over(&self) -> bool {
-        let alive_players_count = self.players.iter().filter(|p| p.get_is_alive()).count();
-        let players_can_respawn = self.players.iter().filter(|p| p.can_respawn()).count();
-        alive_players_count <= 1 && players_can_respawn <= 1
+        self.tick_accumulator = 0.0;
     }
 
     // -------- accessors for renderer --------
@@ -495,6 +954,17 @@ impl Game {
         &mut self.animations
     }
 
+    /// Takes everything that happened since the last call. Frontends should
+    /// drain once per frame, the queue is never trimmed on its own.
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, GameEvent> {
+        self.events.drain(..)
+    }
+
+    /// Events still waiting to be drained.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
     pub fn camera(&self) -> &GameCamera {
         &self.camera
     }
@@ -503,6 +973,10 @@ impl Game {
         &self.players
     }
 
+    pub fn players_mut(&mut self) -> &mut [crate::player::Player] {
+        &mut self.players
+    }
+
     pub fn walls(&self) -> &Vec<Vec<crate::wall::Wall>> {
         &self.walls
     }
@@ -510,36 +984,19 @@ impl Game {
 
   
 
+    /// A tank at `target` is hidden by forest unless `viewer` is right next to it.
+    pub fn is_hidden_from(&self, viewer: [i32; 2], target: [i32; 2]) -> bool {
+        let [x, y] = target;
+        let distance = (target[0] - viewer[0]).abs() + (target[1] - viewer[1]).abs();
+        distance > 1
+            && is_in_bounds(x, y, self.column_count, self.row_count)
+            && self.walls[y as usize][x as usize].hides_tanks()
+    }
+
     pub fn pickups(&self) -> &[crate::pickup::Pickup] {
     &self.pickups
     }
 
-    fn dir_index(dir: crate::transform::LookDirection) -> usize {
-    use crate::transform::LookDirection::*;
-    match dir { Up => 0, Right => 1, Down => 2, Left => 3 }
-}
-
-fn clear_line_of_sight(&self, from: [i32; 2], to: [i32; 2]) -> bool {
-    use std::cmp::{min, max};
-    if from[0] == to[0] {
-        let x = from[0];
-        let (y0, y1) = (min(from[1], to[1]), max(from[1], to[1]));
-        for y in (y0 + 1)..y1 {
-            if self.walls[y as usize][x as usize].is_solid() { return false; }
-        }
-        true
-    } else if from[1] == to[1] {
-        let y = from[1];
-        let (x0, x1) = (min(from[0], to[0]), max(from[0], to[0]));
-        for x in (x0 + 1)..x1 {
-            if self.walls[y as usize][x as usize].is_solid() { return false; }
-        }
-        true
-    } else {
-        false 
-    }
-}
-    
     //
     fn think_bots(&mut self, dt: f64) {
 
@@ -547,10 +1004,14 @@ fn clear_line_of_sight(&self, from: [i32; 2], to: [i32; 2]) -> bool {
 
     use crate::transform::LookDirection::{Up, Right, Down, Left};
 
-    let human_pos = self.players[0].get_position();
-
-    for i in 1..self.players.len() {
-        if self.bot_brains[i].is_none() || !self.players[i].get_is_alive() {
+    for i in 0..self.players.len() {
+        if self.bot_brains[i].is_none() {
+            continue;
+        }
+        // dead bots just hold fire, in case respawning waits for it
+        if !self.players[i].get_is_alive() {
+            self.players[i].release_all();
+            self.players[i].set_action(InputAction::Fire, true);
             continue;
         }
 
@@ -559,20 +1020,74 @@ fn clear_line_of_sight(&self, from: [i32; 2], to: [i32; 2]) -> bool {
             let br = self.bot_brains[i].as_mut().unwrap();
             br.think_timer -= dt;
             br.fire_cd     -= dt;
+            br.revenge_time -= dt;
+            if br.revenge_time <= 0.0 { br.last_attacker = None; }
+        }
+
+        // 2) who to go after, looked at again with every decision
+        if self.bot_brains[i].as_ref().unwrap().think_timer <= 0.0 {
+            let target = self.pick_target(i);
+            self.bot_brains[i].as_mut().unwrap().target = target;
         }
+        let target = self.bot_brains[i].as_ref().unwrap().target
+            .and_then(|id| self.players.iter().position(|p| p.get_id() == id))
+            .filter(|&j| self.players[j].get_is_alive());
 
         // snapshots
-        let (think_timer, fire_cd) = {
+        let (think_timer, fire_cd, config) = {
             let br = self.bot_brains[i].as_ref().unwrap();
-            (br.think_timer, br.fire_cd)
+            (br.think_timer, br.fire_cd, br.config)
         };
+        let skill = config.skill();
         let my_pos = self.players[i].get_position();
+        let occupied = |players: &[Player], np: [i32; 2]| {
+            players.iter().enumerate()
+                .any(|(j, p)| j != i && p.get_is_alive() && p.get_position() == np)
+        };
 
-        // 
+        // follow the path to the goal, planned again once the goal moved,
+        // a wall came or went, or the bot got pushed off it
         let mut chosen_dir = None;
+        let mut arrived = false;
+        let mut breaching = false;
+        let target_pos = target.map(|j| self.players[j].get_position());
+        let goal = self.bot_goal(i, target_pos);
         if think_timer <= 0.0 {
-            let dx = human_pos[0] - my_pos[0];
-            let dy = human_pos[1] - my_pos[1];
+            let revision = self.walls_revision;
+            let brick_cost = match config.personality {
+                BotPersonality::WallBreaker => WALL_BREAKER_BRICK_COST,
+                _ => nav::BRICK_HIT_COST,
+            };
+            let br = self.bot_brains[i].as_mut().unwrap();
+            let on_path = br.path.as_mut().is_some_and(|path| path.advance(my_pos));
+            let stale = !on_path
+                || br.path_revision != revision
+                || br.path.as_ref().and_then(Path::goal) != Some(goal);
+            if stale {
+                br.path = nav::find_path_with(&self.walls, my_pos, goal, brick_cost);
+                br.path_revision = revision;
+            }
+            let has_path = br.path.is_some();
+            let next = br.path.as_ref().and_then(Path::next);
+
+            // a brick on the way is faced and shot down; another tank in the way
+            // is driven around, and next to the target or at home there is
+            // nowhere left to go
+            match next {
+                None => arrived = has_path,
+                Some(np) if np == goal && occupied(&self.players, np) => arrived = true,
+                Some(np) if !occupied(&self.players, np) => {
+                    chosen_dir = LookDirection::towards(my_pos, np);
+                    let [x, y] = np;
+                    breaching = self.walls[y as usize][x as usize].flags().is_destructible();
+                }
+                _ => {}
+            }
+        }
+        // no way there at all, or a tank in the way: head straight for it
+        if think_timer <= 0.0 && chosen_dir.is_none() && !arrived {
+            let dx = goal[0] - my_pos[0];
+            let dy = goal[1] - my_pos[1];
 
             let pref = if dx.abs() > dy.abs() {
                 if dx > 0 { [Right, Down, Up, Left] } else { [Left, Down, Up, Right] }
@@ -586,9 +1101,8 @@ fn clear_line_of_sight(&self, from: [i32; 2], to: [i32; 2]) -> bool {
 
                 let blocked =
                     !is_in_bounds(x, y, self.column_count, self.row_count) ||
-                    self.walls[y as usize][x as usize].is_solid() ||
-                    self.players.iter().enumerate()
-                        .any(|(j, p)| j != i && p.get_is_alive() && p.get_position() == np);
+                    self.walls[y as usize][x as usize].blocks_tanks() ||
+                    occupied(&self.players, np);
 
                 if !blocked {
                     chosen_dir = Some(dir);
@@ -597,43 +1111,198 @@ fn clear_line_of_sight(&self, from: [i32; 2], to: [i32; 2]) -> bool {
             }
         }
 
-        // 
-        let (aligned, aim_dir) = if my_pos[0] == human_pos[0] {
-            (true, if human_pos[1] > my_pos[1] { Down } else { Up })
-        } else if my_pos[1] == human_pos[1] {
-            (true, if human_pos[0] > my_pos[0] { Right } else { Left })
+        // 3) bullets that can hurt it: never drive into one's way, and when one
+        // is coming, shoot it down head-on if there is time and a shot ready,
+        // else step aside; sharper bots notice more often
+        let mut counter_dir = None;
+        if think_timer <= 0.0 {
+            let threats = self.bullet_threats(i, BOT_DODGE_LOOKAHEAD);
+            let arrival = |tile: [i32; 2]| {
+                threats.iter().filter(|(t, _, _)| *t == tile).map(|&(_, tick, _)| tick).min()
+            };
+
+            let walks_into_fire = chosen_dir
+                .map(|dir| dir.position_from(&my_pos))
+                .is_some_and(|np| arrival(np).is_some_and(|tick| tick <= 1));
+            if walks_into_fire {
+                chosen_dir = None;
+                breaching = false;
+            }
+
+            let incoming = threats
+                .iter()
+                .filter(|(tile, _, _)| *tile == my_pos)
+                .min_by_key(|&&(_, tick, _)| tick)
+                .copied();
+            if let Some((_, tick, direction)) = incoming {
+                if self.rng.gen_bool(skill.dodge_chance) {
+                    if tick >= 2 && !self.players[i].is_reloading() {
+                        counter_dir = Some(direction.opposite());
+                    } else {
+                        let escape = [Up, Right, Down, Left].into_iter().find(|dir| {
+                            let np = dir.position_from(&my_pos);
+                            let [x, y] = np;
+                            is_in_bounds(x, y, self.column_count, self.row_count)
+                                && !self.walls[y as usize][x as usize].blocks_tanks()
+                                && !occupied(&self.players, np)
+                                && arrival(np).is_none()
+                        });
+                        if escape.is_some() {
+                            chosen_dir = escape;
+                            breaching = false;
+                        }
+                    }
+                }
+            }
+        }
+
+        // no target: nothing to aim at
+        let target_pos = target_pos.unwrap_or(my_pos);
+        let (aligned, aim_dir) = if target.is_none() {
+            (false, *self.players[i].get_direction())
+        } else if my_pos[0] == target_pos[0] {
+            (true, if target_pos[1] > my_pos[1] { Down } else { Up })
+        } else if my_pos[1] == target_pos[1] {
+            (true, if target_pos[0] > my_pos[0] { Right } else { Left })
         } else {
             (false, *self.players[i].get_direction())
         };
 
-        let manhattan = (human_pos[0] - my_pos[0]).abs() + (human_pos[1] - my_pos[1]).abs();
-        let los_ok = aligned && Self::los_on_walls(&self.walls, my_pos, human_pos);
-        let close_override = aligned && manhattan <= 2; // shoot if very close anyway
-
-        let want_fire = (los_ok || close_override) && fire_cd <= 0.0;
+        let aligned = aligned && !self.is_hidden_from(my_pos, target_pos);
+
+        // lined up, the target is shot at through any bricks in between, but
+        // not through concrete; no point shooting at a tank that cannot be hurt
+        let target_protected = target.is_some_and(|j| self.players[j].is_invulnerable());
+        let at_target = aligned && !target_protected && self.is_shot_useful(i, aim_dir);
+        let want_fire = counter_dir.is_some() || ((at_target || breaching) && fire_cd <= 0.0);
+
+        // a good shot stops and turns to the target first, a sloppy one fires
+        // wherever the tank happens to look, as long as that hits something
+        let take_aim =
+            counter_dir.is_none() && at_target && want_fire && self.rng.gen_bool(skill.aim_accuracy);
+        let fire_dir = match counter_dir {
+            Some(dir) => dir,
+            None if take_aim => aim_dir,
+            None => chosen_dir.unwrap_or(*self.players[i].get_direction()),
+        };
+        let want_fire = want_fire && (counter_dir.is_some() || self.is_shot_useful(i, fire_dir));
 
         // 
         {
             let p = &mut self.players[i];
-            for k in 0..4 { p.set_move_pressed(k, false); }
+            p.release_all();
 
-            if aligned { p.set_direction(aim_dir); } // face target if same row/col
+            // face the bullet to shoot down, else the target if same row/col
+            if let Some(dir) = counter_dir {
+                p.set_direction(dir);
+            } else if aligned && (take_aim || !want_fire) {
+                p.set_direction(aim_dir);
+            }
 
-            if let Some(dir) = chosen_dir {
-                p.set_move_pressed(Self::dir_index(dir), true);
+            if let Some(dir) = chosen_dir.filter(|_| !take_aim && counter_dir.is_none()) {
+                p.on_press(InputAction::from_direction(dir));
             }
 
-            p.set_fire_pressed(want_fire);
+            p.set_action(InputAction::Fire, want_fire);
         }
         {
             let br = self.bot_brains[i].as_mut().unwrap();
-            if think_timer <= 0.0 { br.think_timer = 0.18; }
-            if want_fire        { br.fire_cd     = 0.60; } // tweak
+            if think_timer <= 0.0 { br.think_timer = skill.reaction_time; }
+            if want_fire        { br.fire_cd     = skill.fire_cooldown; }
         }
     }
 }
 
+    // a shot by tank `index` going `direction` would hit an enemy or a wall
+    // it can damage first; concrete, the border, an ally or nothing is a waste
+    fn is_shot_useful(&self, index: usize, direction: LookDirection) -> bool {
+        let me = &self.players[index];
+        let power = me.get_shot_power();
+        let mut position = me.get_position();
+        loop {
+            position = direction.position_from(&position);
+            let [x, y] = position;
+            if !is_in_bounds(x, y, self.column_count, self.row_count) {
+                return false;
+            }
+            let struck = self.players.iter().find(|p| p.get_is_alive() && p.get_position() == position);
+            if let Some(player) = struck {
+                return !player.is_ally_of(me);
+            }
+            let wall = &self.walls[y as usize][x as usize];
+            if wall.blocks_bullets() {
+                return wall.flags().damage_from(power) > 0;
+            }
+        }
+    }
 
+    /// Who bot `index` goes after: the closest enemy it can see, but a tank
+    /// lined up to shoot it, whoever shot it lately and the one it already
+    /// chases count as closer than they are. Allies are left alone.
+    fn pick_target(&self, index: usize) -> Option<u32> {
+        let me = &self.players[index];
+        let brain = self.bot_brains[index].as_ref()?;
+        let position = me.get_position();
+
+        self.players
+            .iter()
+            .filter(|p| p.get_is_alive() && !p.is_ally_of(me))
+            .filter(|p| {
+                brain.last_attacker == Some(p.get_id()) || !self.is_hidden_from(position, p.get_position())
+            })
+            .min_by_key(|p| {
+                let [x, y] = p.get_position();
+                let mut score = (x - position[0]).abs() + (y - position[1]).abs();
+                if self.is_threat(p, position) { score -= BOT_THREAT_BONUS; }
+                if brain.last_attacker == Some(p.get_id()) { score -= BOT_REVENGE_BONUS; }
+                if brain.target == Some(p.get_id()) { score -= BOT_TARGET_STICKINESS; }
+                (score, p.get_id())
+            })
+            .map(Player::get_id)
+    }
+
+    // `player` is lined up with `position`, facing it, with nothing in between
+    fn is_threat(&self, player: &Player, position: [i32; 2]) -> bool {
+        let from = player.get_position();
+        let facing = match [position[0] - from[0], position[1] - from[1]] {
+            [0, dy] if dy < 0 => LookDirection::Up,
+            [0, dy] if dy > 0 => LookDirection::Down,
+            [dx, 0] if dx < 0 => LookDirection::Left,
+            [dx, 0] if dx > 0 => LookDirection::Right,
+            _ => return false,
+        };
+        *player.get_direction() == facing && Self::los_on_walls(&self.walls, from, position)
+    }
+
+    /// Where bot `index` is headed: a pickup close enough for its taste, its
+    /// spawn if it camps and `target` is far from there (or there is none),
+    /// else `target`.
+    fn bot_goal(&self, index: usize, target: Option<[i32; 2]>) -> [i32; 2] {
+        let home = self.players[index].get_spawn().0;
+        let Some(config) = self.bot_brains[index].as_ref().map(|br| br.config) else {
+            return target.unwrap_or(home);
+        };
+        let position = self.players[index].get_position();
+        let distance = |a: [i32; 2], b: [i32; 2]| (a[0] - b[0]).abs() + (a[1] - b[1]).abs();
+
+        let reach = config.pickup_reach() as i32;
+        let pickup = self
+            .pickups
+            .iter()
+            .map(|pickup| *pickup.get_position())
+            .filter(|&spot| distance(position, spot) <= reach)
+            .min_by_key(|&spot| distance(position, spot));
+        if let Some(spot) = pickup {
+            return spot;
+        }
+
+        let camping = config.personality == BotPersonality::Camper;
+        match target {
+            Some(target) if camping && distance(home, target) > BOT_CAMP_RANGE => home,
+            Some(target) => target,
+            None => home,
+        }
+    }
 
     //
 
@@ -648,14 +1317,14 @@ fn clear_line_of_sight(&self, from: [i32; 2], to: [i32; 2]) -> bool {
             let x = from[0];
             let (y0, y1) = (min(from[1], to[1]), max(from[1], to[1]));
             for y in (y0 + 1)..y1 {
-                if walls[y as usize][x as usize].is_solid() { return false; }
+                if walls[y as usize][x as usize].blocks_sight() { return false; }
             }
             true
         } else if from[1] == to[1] {
             let y = from[1];
             let (x0, x1) = (min(from[0], to[0]), max(from[0], to[0]));
             for x in (x0 + 1)..x1 {
-                if walls[y as usize][x as usize].is_solid() { return false; }
+                if walls[y as usize][x as usize].blocks_sight() { return false; }
             }
             true
         } else {
@@ -670,67 +1339,425 @@ fn clear_line_of_sight(&self, from: [i32; 2], to: [i32; 2]) -> bool {
     }
 
 
-    
-//
-    fn check_victory(&mut self) {
-        use crate::game::GameState::*;
-        if self.state != Playing { return; }
 
-        let human_alive_or_can_respawn = self.players.get(0)
-            .map(|p| p.get_is_alive() || p.can_respawn())
-            .unwrap_or(false);
+    // -------- match flow --------
 
-      
-        let any_enemy_alive = self.players.iter().skip(1).any(|p| p.get_is_alive());
+    /// Still in the round: alive, or dead with lives left to come back.
+    fn is_in_round(player: &Player) -> bool {
+        player.get_is_alive() || player.can_respawn()
+    }
+
+    /// The round ends when at most one side (a tank alone, or a team) is left,
+    /// or when every human and their team is out (nobody wants to watch the
+    /// bots finish it). The side left wins it, every tank of a team scores.
+    fn check_round_over(&mut self) {
+        if self.state != GameState::Playing { return; }
+
+        let remaining = self.players.iter().filter(|p| Self::is_in_round(p)).collect::<Vec<_>>();
+        let mut sides: Vec<&Player> = vec![];
+        for player in &remaining {
+            if !sides.iter().any(|side| side.is_ally_of(player)) {
+                sides.push(player);
+            }
+        }
+        let humans = self.players.iter().enumerate()
+            .filter(|(i, _)| self.bot_brains[*i].is_none())
+            .map(|(_, p)| p)
+            .collect::<Vec<_>>();
+        let humans_out = !humans.is_empty()
+            && !remaining.iter().any(|p| humans.iter().any(|human| human.is_ally_of(p)));
+
+        if sides.len() > 1 && !humans_out { return; }
+
+        let winners = match sides.as_slice() {
+            [winner] => (0..self.players.len())
+                .filter(|&i| self.players[i].is_ally_of(winner))
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        self.round_winner = sides.first().filter(|_| sides.len() == 1).map(|winner| winner.get_id());
+        for index in winners {
+            self.round_wins[index] += 1;
+        }
+        self.set_state(GameState::RoundOver);
+    }
 
-        if human_alive_or_can_respawn && !any_enemy_alive {
-            self.state = Won;
-            self.state_time = 0.0;
-        } else if !human_alive_or_can_respawn {
-            self.state = Lost;
-            self.state_time = 0.0;
+    fn finish_round(&mut self) {
+        if self.match_winner().is_some() {
+            self.set_state(GameState::MatchOver);
+        } else {
+            self.start_round();
         }
     }
 
+    fn start_round(&mut self) {
+        if self.round > 0 {
+            self.reset_round();
+        }
+        // tanks already stand on their spawns through the countdown, no sparkle
+        for i in 0..self.players.len() {
+            self.protect_spawn(i, 0.0);
+        }
+        self.round += 1;
+        self.round_winner = None;
+        self.set_state(GameState::Countdown);
+    }
 
-//
+    fn set_state(&mut self, state: GameState) {
+        self.change_state(state);
+        self.state_time = 0.0;
+    }
 
+    // every transition goes through here so none of them is missed by listeners
+    fn change_state(&mut self, state: GameState) {
+        if self.state != state {
+            self.events.push(GameEvent::StateChanged { from: self.state, to: state });
+        }
+        self.state = state;
+    }
 
-     pub fn state(&self) -> GameState { self.state }
-    pub fn state_time(&self) -> f32 { self.state_time }
+    /// Menu (or a finished match) -> countdown of the first round.
+    pub fn start_match(&mut self) {
+        if self.state != GameState::Menu {
+            self.reset();
+        }
+        self.start_round();
+    }
 
+    pub fn pause(&mut self) {
+        if self.state == GameState::Playing {
+            self.change_state(GameState::Paused);
+        }
+    }
 
-     pub fn resume(&mut self) {
-        self.state = GameState::Playing;
-        self.state_time = 0.0;
+    /// Back from `Paused`; the time already spent playing is kept.
+    pub fn resume(&mut self) {
+        if self.state == GameState::Paused {
+            self.change_state(GameState::Playing);
+        }
     }
 
+    pub fn toggle_pause(&mut self) {
+        match self.state {
+            GameState::Playing => self.pause(),
+            GameState::Paused => self.resume(),
+            _ => {}
+        }
+    }
 
+    pub fn state(&self) -> GameState { self.state }
 
-}
+    /// Seconds spent in the current state, smoothed between ticks.
+    pub fn state_time(&self) -> f32 {
+        match self.state {
+            GameState::Menu | GameState::Paused | GameState::MatchOver => self.state_time,
+            _ => self.state_time + self.tick_accumulator as f32,
+        }
+    }
 
+    /// Seconds left in a timed phase (`Countdown`, `RoundOver`), `None` otherwise.
+    pub fn phase_remaining(&self) -> Option<f32> {
+        let duration = match self.state {
+            GameState::Countdown => self.rules.countdown_duration,
+            GameState::RoundOver => self.rules.round_over_duration,
+            _ => return None,
+        };
+        Some((duration as f32 - self.state_time()).max(0.0))
+    }
 
+    /// 1-based number of the current round, 0 before the match starts.
+    pub fn round(&self) -> u32 { self.round }
 
+    /// Rounds won so far, indexed like `players()`.
+    pub fn round_wins(&self) -> &[u32] { &self.round_wins }
 
+    /// Id of whoever took the last finished round; `None` for a draw.
+    pub fn round_winner(&self) -> Option<u32> { self.round_winner }
 
+    pub fn match_winner(&self) -> Option<u32> {
+        self.round_wins
+            .iter()
+            .position(|&wins| wins >= self.rules.rounds_to_win)
+            .map(|index| self.players[index].get_id())
+    }
 
-#[derive(Clone, Copy, Debug)]
-struct BotBrain {
-    think_timer: f64,  //
-    fire_cd: f64,      
 }
 
-impl Default for BotBrain {
-    fn default() -> Self { Self { think_timer: 0.0, fire_cd: 0.0 } }
-}
+
+
+
+
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameState {
     Menu,  
+    Countdown,
     Playing,
-    Won,
-    Lost,
-
+    Paused,
+    RoundOver,
+    MatchOver,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::char_to_tile;
+
+    // a level drawn from `rows` with one bot per spawn, all facing down
+    fn arena(rows: &[&str], spawns: &[[i32; 2]], teams: Vec<u32>) -> Game {
+        arena_with(rows, spawns, GameRules { humans: 0, teams, ..GameRules::default() })
+    }
+
+    fn arena_with(rows: &[&str], spawns: &[[i32; 2]], rules: GameRules) -> Game {
+        let level = Level {
+            name: "test".to_string(),
+            width: rows[0].len() as u8,
+            height: rows.len() as u8,
+            tiles: rows.iter().map(|row| row.chars().map(|c| char_to_tile(c).unwrap()).collect()).collect(),
+            spawns: spawns
+                .iter()
+                .map(|&position| SpawnPoint { position, facing: LookDirection::Down })
+                .collect(),
+            pickups: vec![],
+        };
+        let mut game = Game::new(rules, 1);
+        game.load_level(level);
+        game
+    }
+
+    fn run_ticks(game: &mut Game, ticks: u32) {
+        (0..ticks).for_each(|_| game.tick());
+    }
+
+    const OPEN: [&str; 7] = ["XXXXXXX", "X.....X", "X.....X", "X.....X", "X.....X", "X.....X", "XXXXXXX"];
+    const CORNERS: [[i32; 2]; 4] = [[1, 1], [5, 5], [1, 5], [5, 1]];
+
+    #[test]
+    fn clock_freezes_only_enemies() {
+        let mut game = arena(&OPEN, &CORNERS, vec![0, 0, 1, 1]);
+        game.apply_pickup(0, PickupType::Clock);
+
+        let frozen = game.players.iter().map(Player::is_frozen).collect::<Vec<_>>();
+        assert_eq!(frozen, vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn grenade_spares_allies() {
+        let mut game = arena(&OPEN, &CORNERS, vec![0, 0, 1, 1]);
+        game.apply_pickup(0, PickupType::Grenade);
+
+        let alive = game.players.iter().map(Player::get_is_alive).collect::<Vec<_>>();
+        assert_eq!(alive, vec![true, true, false, false]);
+        assert_eq!(game.players[0].get_kills(), 2);
+        let victims = game
+            .drain_events()
+            .filter_map(|event| match event {
+                GameEvent::TankDestroyed { killer: 0, victim, .. } => Some(victim),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(victims, vec![2, 3]);
+    }
+
+    #[test]
+    fn shovel_gives_chipped_bricks_back() {
+        let rows = ["XXXXXXX", "XBBBB.X", "XB.BB.X", "XBBBB.X", "X.....X", "X.....X", "XXXXXXX"];
+        let mut game = arena(&rows, &[[2, 2], [5, 5]], vec![]);
+        game.walls[1][1].damage(1);
+        let revision = game.walls_revision;
+
+        game.apply_pickup(0, PickupType::Shovel);
+        assert!(game.walls_revision > revision);
+        for (x, y) in [(1, 1), (2, 1), (3, 1), (1, 2), (3, 2), (1, 3), (3, 3)] {
+            assert_eq!(game.walls[y][x].variant(), WallType::Concrete, "{}, {}", x, y);
+        }
+        // the tile the spawn faces stays open
+        assert_eq!(game.walls[3][2].variant(), WallType::Brick);
+
+        game.update_fortifications(game.rules.shovel_duration);
+        assert!(game.fortifications.is_empty());
+        assert_eq!(game.walls[1][1].variant(), WallType::Brick);
+        assert_eq!(game.walls[1][1].get_hit_points(), 2);
+        for (x, y) in [(2, 1), (3, 1), (1, 2), (3, 2), (1, 3), (3, 3)] {
+            assert_eq!(game.walls[y][x].variant(), WallType::Brick, "{}, {}", x, y);
+            assert!(!game.walls[y][x].is_damaged(), "{}, {}", x, y);
+        }
+    }
+
+    #[test]
+    fn shovel_with_nothing_to_fortify_keeps_the_walls() {
+        // every tile around the spawn is border, the exit or another tank
+        let mut game = arena(&OPEN, &[[1, 1], [2, 1], [2, 2]], vec![]);
+        let revision = game.walls_revision;
+
+        game.apply_pickup(0, PickupType::Shovel);
+        assert_eq!(game.walls_revision, revision);
+    }
+
+    #[test]
+    fn spawns_are_protected_once() {
+        // two people who never press anything, so nothing shoots
+        let rules = GameRules { humans: 2, spawn_protection_time: 2.0, respawn_delay: 1.0, ..GameRules::default() };
+        let mut game = arena_with(&OPEN, &CORNERS[..2], rules);
+        game.start_match();
+        while game.state() != GameState::Playing {
+            game.tick();
+        }
+
+        // round start: protected right away, no sparkle
+        assert!(game.players[1].is_invulnerable());
+        assert!(!game.players[1].has_effect(StatusEffectKind::Spawning));
+        run_ticks(&mut game, 21);
+        assert!(!game.players[1].is_invulnerable());
+
+        assert!(game.players[1].destroy());
+        while !game.players[1].get_is_alive() {
+            game.tick();
+        }
+        assert!(game.players[1].has_effect(StatusEffectKind::Spawning));
+        assert!(game.animations.iter().any(|animation| animation.get_player() == Some(1)));
+
+        // 0.9s of sparkle, then invulnerable for the rules' 2s
+        run_ticks(&mut game, 8);
+        assert!(game.players[1].has_effect(StatusEffectKind::Spawning));
+        run_ticks(&mut game, 2);
+        assert!(!game.players[1].has_effect(StatusEffectKind::Spawning));
+        assert!(game.animations.iter().all(|animation| animation.get_player() != Some(1)));
+        assert!(game.players[1].is_invulnerable());
+        run_ticks(&mut game, 18);
+        assert!(game.players[1].is_invulnerable());
+        run_ticks(&mut game, 2);
+        assert!(!game.players[1].is_invulnerable());
+    }
+
+    #[test]
+    fn bot_goal_follows_the_personality() {
+        let mut game = arena(&OPEN, &CORNERS, vec![]);
+        game.pickups.push(Pickup::new([5, 3], PickupType::Star));
+        let goal_as = |game: &mut Game, personality| {
+            game.set_bot_config(0, BotConfig { personality, ..BotConfig::default() });
+            game.bot_goal(0, Some([5, 5]))
+        };
+
+        // the star is 6 tiles away: only a collector cares, a camper stays
+        // home while the target is out of its range
+        assert_eq!(goal_as(&mut game, BotPersonality::Hunter), [5, 5]);
+        assert_eq!(goal_as(&mut game, BotPersonality::Collector), [5, 3]);
+        assert_eq!(goal_as(&mut game, BotPersonality::Camper), [1, 1]);
+        assert_eq!(goal_as(&mut game, BotPersonality::WallBreaker), [5, 5]);
+    }
+
+    #[test]
+    fn respawns_ignore_teammates_next_to_the_spawn() {
+        let mut game = arena(&OPEN, &[[1, 1], [5, 5], [1, 5]], vec![0, 0, 1]);
+        game.players[0].destroy();
+        game.players[1].set_position([2, 1]);
+        game.players[2].set_position([5, 5]);
+
+        // the teammate next door is no reason to go elsewhere
+        assert_eq!(game.safest_spawn(0), Some(([1, 1], LookDirection::Down)));
+
+        // but standing on the spawn it is in the way
+        game.players[1].set_position([1, 1]);
+        assert_eq!(game.safest_spawn(0), Some(([1, 5], LookDirection::Down)));
+    }
+
+    #[test]
+    fn targets_enemies_only() {
+        let mut game = arena(&OPEN, &[[1, 1], [2, 1], [5, 5]], vec![0, 0, 1]);
+        assert_eq!(game.pick_target(0), Some(2));
+
+        game.players[2].destroy();
+        assert_eq!(game.pick_target(0), None);
+    }
+
+    #[test]
+    fn targets_the_last_attacker_over_a_closer_enemy() {
+        let mut game = arena(&OPEN, &[[1, 1], [3, 1], [5, 5]], vec![]);
+        assert_eq!(game.pick_target(0), Some(1));
+
+        game.bot_brains[0].as_mut().unwrap().last_attacker = Some(2);
+        assert_eq!(game.pick_target(0), Some(2));
+    }
+
+    #[test]
+    fn targets_a_lined_up_threat_over_a_closer_enemy() {
+        // 1 is 3 tiles off but looks away, 2 is 4 tiles off and aims at 0
+        let mut game = arena(&OPEN, &[[1, 1], [4, 1], [1, 5]], vec![]);
+        game.players[1].set_direction(LookDirection::Down);
+        game.players[2].set_direction(LookDirection::Right);
+        assert_eq!(game.pick_target(0), Some(1));
+
+        game.players[2].set_direction(LookDirection::Up);
+        assert_eq!(game.pick_target(0), Some(2));
+
+        // a wall in between takes the threat away
+        game.walls[3][1] = Wall::new([1, 3]).concrete();
+        assert_eq!(game.pick_target(0), Some(1));
+    }
+
+    #[test]
+    fn threats_are_enemy_bullets_up_to_the_first_wall() {
+        let mut game = arena(&OPEN, &[[1, 1], [5, 5], [1, 5]], vec![0, 0, 1]);
+        game.walls[1][3] = Wall::new([3, 1]).brick();
+        game.bullets = vec![
+            Projectile::new(2, [3, 4], LookDirection::Up),
+            Projectile::new(2, [5, 3], LookDirection::Left).with_speed(2),
+            // its own and a teammate's cannot hurt it
+            Projectile::new(0, [2, 5], LookDirection::Up),
+            Projectile::new(1, [5, 2], LookDirection::Left),
+        ];
+
+        use LookDirection::{Left, Up};
+        assert_eq!(game.bullet_threats(0, 4), vec![
+            ([3, 3], 1, Up),
+            ([3, 2], 2, Up),
+            ([4, 3], 1, Left),
+            ([3, 3], 1, Left),
+            ([2, 3], 2, Left),
+            ([1, 3], 2, Left),
+        ]);
+        assert_eq!(game.bullet_threats(0, 1), vec![([3, 3], 1, Up), ([4, 3], 1, Left), ([3, 3], 1, Left)]);
+
+        // the other side sees the teammates' bullets instead
+        let seen_by_enemy = game.bullet_threats(2, 1).into_iter().map(|(tile, _, _)| tile).collect::<Vec<_>>();
+        assert_eq!(seen_by_enemy, vec![[2, 4], [4, 2]]);
+    }
+
+    #[test]
+    fn shots_are_only_useful_at_what_they_can_hurt() {
+        let rows = ["XXXXXXX", "X.C.B.X", "X.....X", "X.....X", "X.....X", "X.....X", "XXXXXXX"];
+        let mut game = arena(&rows, &[[4, 3], [4, 5], [1, 3]], vec![0, 0, 1]);
+
+        use LookDirection::{Down, Left, Right, Up};
+        assert!(game.is_shot_useful(0, Up), "brick");
+        assert!(!game.is_shot_useful(0, Down), "teammate");
+        assert!(game.is_shot_useful(0, Left), "enemy");
+        assert!(!game.is_shot_useful(0, Right), "border");
+
+        game.players[0].set_position([2, 4]);
+        assert!(!game.is_shot_useful(0, Up), "concrete");
+        for _ in 0..3 {
+            game.players[0].add_star();
+        }
+        assert!(game.is_shot_useful(0, Up), "concrete with three stars");
+    }
+
+    #[test]
+    fn wall_breakers_plan_through_bricks_hunters_drive_around() {
+        let rows = ["XXXXXXX", "X.....X", "XBBB..X", "X.....X", "XXXXXXX"];
+        let rules = GameRules { humans: 1, ..GameRules::default() };
+        let next_step = |personality| {
+            let mut game = arena_with(&rows, &[[1, 3], [1, 1]], rules.clone());
+            game.set_bot_config(1, BotConfig { personality, ..BotConfig::default() });
+            game.state = GameState::Playing;
+            game.think_bots(game.update_interval);
+            game.bot_brains[1].as_ref().unwrap().path.as_ref().and_then(Path::next)
+        };
+
+        // around is 8 tiles, through the brick 2 plus the hits it takes
+        assert_eq!(next_step(BotPersonality::Hunter), Some([2, 1]));
+        assert_eq!(next_step(BotPersonality::WallBreaker), Some([1, 2]));
+    }
+}
 
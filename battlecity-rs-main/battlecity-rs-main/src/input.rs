@@ -0,0 +1,60 @@
+use crate::transform::LookDirection;
+
+/// Everything a tank can be told to do. Frontends translate their own keys
+/// (or bots, network peers and replays their own decisions) into these.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    MoveUp,
+    MoveRight,
+    MoveDown,
+    MoveLeft,
+    Fire,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 5] = [
+        InputAction::MoveUp,
+        InputAction::MoveRight,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::Fire,
+    ];
+
+    pub fn from_direction(direction: LookDirection) -> InputAction {
+        match direction {
+            LookDirection::Up => InputAction::MoveUp,
+            LookDirection::Right => InputAction::MoveRight,
+            LookDirection::Down => InputAction::MoveDown,
+            LookDirection::Left => InputAction::MoveLeft,
+        }
+    }
+
+    /// Movement direction for the move actions, `None` for everything else.
+    pub fn direction(&self) -> Option<LookDirection> {
+        match self {
+            InputAction::MoveUp => Some(LookDirection::Up),
+            InputAction::MoveRight => Some(LookDirection::Right),
+            InputAction::MoveDown => Some(LookDirection::Down),
+            InputAction::MoveLeft => Some(LookDirection::Left),
+            InputAction::Fire => None,
+        }
+    }
+}
+
+/// An action addressed to one player, pressed or released.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerCommand {
+    pub player_id: u32,
+    pub action: InputAction,
+    pub pressed: bool,
+}
+
+impl PlayerCommand {
+    pub fn press(player_id: u32, action: InputAction) -> PlayerCommand {
+        PlayerCommand { player_id, action, pressed: true }
+    }
+
+    pub fn release(player_id: u32, action: InputAction) -> PlayerCommand {
+        PlayerCommand { player_id, action, pressed: false }
+    }
+}
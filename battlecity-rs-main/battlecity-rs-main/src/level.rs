@@ -0,0 +1,475 @@
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    transform::LookDirection,
+    wall::{Wall, WallType},
+};
+
+/// Most tanks a level can place, one per tank sprite set.
+pub const MAX_SPAWNS: usize = 4;
+
+/// A hand-authored map. On disk it is a TOML file whose `tiles` string draws
+/// the grid, one character per tile:
+///
+/// ```toml
+/// name = "Tiny"
+/// width = 7
+/// height = 5
+/// tiles = """
+/// XXXXXXX
+/// X..B..X
+/// X.CNC.X
+/// X..B..X
+/// XXXXXXX
+/// """
+/// pickups = [[3, 1]]
+///
+/// [[spawns]]
+/// slot = 0
+/// position = [1, 1]
+/// facing = "down"
+///
+/// [[spawns]]
+/// slot = 1
+/// position = [5, 3]
+/// facing = "up"
+/// ```
+///
+/// Tiles: `.` empty, `B` brick, `C` concrete, `N` net, `X` border, `W` water,
+/// `I` ice, `F` forest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub name: String,
+    pub width: u8,
+    pub height: u8,
+    /// Row-major, `tiles[y][x]`.
+    pub tiles: Vec<Vec<WallType>>,
+    /// Ordered by slot: `spawns[i]` is where player `i` starts.
+    pub spawns: Vec<SpawnPoint>,
+    /// Where pickups may appear; empty means any empty tile.
+    pub pickups: Vec<[i32; 2]>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpawnPoint {
+    pub position: [i32; 2],
+    pub facing: LookDirection,
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    /// `tiles` has a different number of rows than `height`.
+    RowCount { expected: u8, found: usize },
+    /// A row of `tiles` is not `width` characters long.
+    RowLength { row: usize, expected: u8, found: usize },
+    UnknownTile { row: usize, column: usize, tile: char },
+    UnknownFacing { slot: usize, facing: String },
+    /// Slots must run 0, 1, .. without gaps or repeats.
+    BadSlot { slot: usize },
+    TooFewSpawns { found: usize },
+    TooManySpawns { found: usize },
+    SpawnOutOfBounds { slot: usize, position: [i32; 2] },
+    SpawnBlocked { slot: usize, position: [i32; 2] },
+    /// Two slots start on the same tile; `other` is the earlier one.
+    SpawnShared { slot: usize, other: usize, position: [i32; 2] },
+    PickupOutOfBounds { position: [i32; 2] },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "cannot read level file: {}", err),
+            LevelError::Parse(err) => write!(f, "malformed level file: {}", err),
+            LevelError::RowCount { expected, found } => {
+                write!(f, "level declares height {} but draws {} rows", expected, found)
+            }
+            LevelError::RowLength { row, expected, found } => write!(
+                f,
+                "row {} is {} tiles long, the level declares width {}",
+                row, found, expected
+            ),
+            LevelError::UnknownTile { row, column, tile } => write!(
+                f,
+                "unknown tile '{}' at column {}, row {} (use . B C N X W I F)",
+                tile, column, row
+            ),
+            LevelError::UnknownFacing { slot, facing } => write!(
+                f,
+                "spawn {} faces '{}', expected up, right, down or left",
+                slot, facing
+            ),
+            LevelError::BadSlot { slot } => {
+                write!(f, "spawn slot {} is repeated or leaves a gap, slots run 0, 1, ..", slot)
+            }
+            LevelError::TooFewSpawns { found } => {
+                write!(f, "level has {} spawn(s), a match needs at least 2", found)
+            }
+            LevelError::TooManySpawns { found } => {
+                write!(f, "level has {} spawns, at most {} are supported", found, MAX_SPAWNS)
+            }
+            LevelError::SpawnOutOfBounds { slot, position } => {
+                write!(f, "spawn {} at {:?} is outside the map", slot, position)
+            }
+            LevelError::SpawnBlocked { slot, position } => {
+                write!(f, "spawn {} at {:?} is inside a solid tile", slot, position)
+            }
+            LevelError::SpawnShared { slot, other, position } => {
+                write!(f, "spawns {} and {} are both at {:?}", other, slot, position)
+            }
+            LevelError::PickupOutOfBounds { position } => {
+                write!(f, "pickup spot {:?} is outside the map", position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+// on-disk shape, checked and converted into `Level`
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LevelFile {
+    #[serde(default)]
+    name: String,
+    width: u8,
+    height: u8,
+    tiles: String,
+    #[serde(default)]
+    pickups: Vec<[i32; 2]>,
+    spawns: Vec<SpawnFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpawnFile {
+    slot: usize,
+    position: [i32; 2],
+    facing: String,
+}
+
+pub fn tile_to_char(tile: WallType) -> char {
+    match tile {
+        WallType::Empty => '.',
+        WallType::Brick => 'B',
+        WallType::Concrete => 'C',
+        WallType::Net => 'N',
+        WallType::Border => 'X',
+        WallType::Water => 'W',
+        WallType::Ice => 'I',
+        WallType::Forest => 'F',
+    }
+}
+
+pub fn char_to_tile(tile: char) -> Option<WallType> {
+    match tile {
+        '.' => Some(WallType::Empty),
+        'B' => Some(WallType::Brick),
+        'C' => Some(WallType::Concrete),
+        'N' => Some(WallType::Net),
+        'X' => Some(WallType::Border),
+        'W' => Some(WallType::Water),
+        'I' => Some(WallType::Ice),
+        'F' => Some(WallType::Forest),
+        _ => None,
+    }
+}
+
+fn facing_name(facing: LookDirection) -> &'static str {
+    match facing {
+        LookDirection::Up => "up",
+        LookDirection::Right => "right",
+        LookDirection::Down => "down",
+        LookDirection::Left => "left",
+    }
+}
+
+fn parse_facing(facing: &str) -> Option<LookDirection> {
+    match facing.trim().to_ascii_lowercase().as_str() {
+        "up" => Some(LookDirection::Up),
+        "right" => Some(LookDirection::Right),
+        "down" => Some(LookDirection::Down),
+        "left" => Some(LookDirection::Left),
+        _ => None,
+    }
+}
+
+impl Level {
+    pub fn from_toml_str(source: &str) -> Result<Level, LevelError> {
+        let file: LevelFile = toml::from_str(source).map_err(LevelError::Parse)?;
+
+        let rows = file
+            .tiles
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        if rows.len() != file.height as usize {
+            return Err(LevelError::RowCount { expected: file.height, found: rows.len() });
+        }
+
+        let mut tiles = Vec::with_capacity(rows.len());
+        for (y, line) in rows.iter().enumerate() {
+            let found = line.chars().count();
+            if found != file.width as usize {
+                return Err(LevelError::RowLength { row: y, expected: file.width, found });
+            }
+
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(x, tile)| {
+                    char_to_tile(tile).ok_or(LevelError::UnknownTile { row: y, column: x, tile })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            tiles.push(row);
+        }
+
+        let mut spawns = file.spawns;
+        spawns.sort_by_key(|spawn| spawn.slot);
+        let spawns = spawns
+            .into_iter()
+            .enumerate()
+            .map(|(index, spawn)| {
+                if spawn.slot != index {
+                    return Err(LevelError::BadSlot { slot: spawn.slot });
+                }
+                let facing = parse_facing(&spawn.facing).ok_or_else(|| LevelError::UnknownFacing {
+                    slot: spawn.slot,
+                    facing: spawn.facing.clone(),
+                })?;
+                Ok(SpawnPoint { position: spawn.position, facing })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let level = Level {
+            name: file.name,
+            width: file.width,
+            height: file.height,
+            tiles,
+            spawns,
+            pickups: file.pickups,
+        };
+        level.validate()?;
+        Ok(level)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Level, LevelError> {
+        let source = fs::read_to_string(path).map_err(LevelError::Io)?;
+        Level::from_toml_str(&source)
+    }
+
+    /// Loads `--level <file>` from a command line, `None` when it is absent.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Level>, LevelError> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--level" {
+                return match args.next() {
+                    Some(path) => Level::load(path).map(Some),
+                    None => Err(LevelError::Io(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "--level needs a file path",
+                    ))),
+                };
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn to_toml_string(&self) -> String {
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|row| row.iter().map(|&tile| tile_to_char(tile)).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let file = LevelFile {
+            name: self.name.clone(),
+            width: self.width,
+            height: self.height,
+            tiles: format!("{}\n", tiles),
+            pickups: self.pickups.clone(),
+            spawns: self
+                .spawns
+                .iter()
+                .enumerate()
+                .map(|(slot, spawn)| SpawnFile {
+                    slot,
+                    position: spawn.position,
+                    facing: facing_name(spawn.facing).to_string(),
+                })
+                .collect(),
+        };
+        toml::to_string(&file).expect("levels always serialize")
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_toml_string())
+    }
+
+    pub fn is_in_bounds(&self, [x, y]: [i32; 2]) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32
+    }
+
+    pub fn tile(&self, [x, y]: [i32; 2]) -> WallType {
+        self.tiles[y as usize][x as usize]
+    }
+
+    /// Checks the invariants `Game` relies on: 2..=4 spawns, all of them on
+    /// the map, on a passable tile and on a tile of their own, and pickup
+    /// spots inside the map.
+    pub fn validate(&self) -> Result<(), LevelError> {
+        if self.spawns.len() < 2 {
+            return Err(LevelError::TooFewSpawns { found: self.spawns.len() });
+        }
+        if self.spawns.len() > MAX_SPAWNS {
+            return Err(LevelError::TooManySpawns { found: self.spawns.len() });
+        }
+
+        for (slot, spawn) in self.spawns.iter().enumerate() {
+            if !self.is_in_bounds(spawn.position) {
+                return Err(LevelError::SpawnOutOfBounds { slot, position: spawn.position });
+            }
+            if self.tile(spawn.position).flags().blocks_tanks {
+                return Err(LevelError::SpawnBlocked { slot, position: spawn.position });
+            }
+            let earlier = self.spawns[..slot].iter().position(|s| s.position == spawn.position);
+            if let Some(other) = earlier {
+                return Err(LevelError::SpawnShared { slot, other, position: spawn.position });
+            }
+        }
+
+        if let Some(&position) = self.pickups.iter().find(|&&p| !self.is_in_bounds(p)) {
+            return Err(LevelError::PickupOutOfBounds { position });
+        }
+        Ok(())
+    }
+
+    /// The wall grid `Game` plays on.
+    pub fn walls(&self) -> Vec<Vec<Wall>> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, &tile)| Wall::new([x as i32, y as i32]).with_variant(tile))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TILES: &str = "
+    XXXXXXX
+    X..B..X
+    X.CNC.X
+    X..B..X
+    XXXXXXX
+";
+
+    // a level file drawing `TILES` with the given spawns and pickup spots
+    fn source(spawns: &[([i32; 2], &str)], pickups: &str) -> String {
+        let mut source = format!(
+            "name = \"Tiny\"\nwidth = 7\nheight = 5\npickups = {}\ntiles = \"\"\"{}\"\"\"\n",
+            pickups, TILES
+        );
+        for (slot, (position, facing)) in spawns.iter().enumerate() {
+            source += &format!(
+                "\n[[spawns]]\nslot = {}\nposition = {:?}\nfacing = \"{}\"\n",
+                slot, position, facing
+            );
+        }
+        source
+    }
+
+    fn parse(spawns: &[[i32; 2]]) -> Result<Level, LevelError> {
+        let spawns = spawns.iter().map(|&position| (position, "down")).collect::<Vec<_>>();
+        Level::from_toml_str(&source(&spawns, "[]"))
+    }
+
+    #[test]
+    fn parses_a_valid_level() {
+        let level = Level::from_toml_str(&source(&[([1, 1], "down"), ([5, 3], "up")], "[[3, 1]]"))
+            .unwrap();
+        assert_eq!(level.name, "Tiny");
+        assert_eq!((level.width, level.height), (7, 5));
+        assert_eq!(level.tile([3, 1]), WallType::Brick);
+        assert_eq!(level.tile([2, 2]), WallType::Concrete);
+        assert_eq!(level.tile([3, 2]), WallType::Net);
+        assert_eq!(level.spawns[1], SpawnPoint { position: [5, 3], facing: LookDirection::Up });
+        assert_eq!(level.pickups, vec![[3, 1]]);
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let level = parse(&[[1, 1], [5, 3]]).unwrap();
+        assert_eq!(Level::from_toml_str(&level.to_toml_string()).unwrap(), level);
+    }
+
+    #[test]
+    fn rejects_too_few_spawns() {
+        assert!(matches!(parse(&[[1, 1]]), Err(LevelError::TooFewSpawns { found: 1 })));
+    }
+
+    #[test]
+    fn rejects_too_many_spawns() {
+        let spawns = [[1, 1], [2, 1], [4, 1], [5, 1], [1, 3]];
+        assert!(matches!(parse(&spawns), Err(LevelError::TooManySpawns { found: 5 })));
+    }
+
+    #[test]
+    fn rejects_a_spawn_out_of_bounds() {
+        assert!(matches!(
+            parse(&[[1, 1], [7, 3]]),
+            Err(LevelError::SpawnOutOfBounds { slot: 1, position: [7, 3] })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_blocked_spawn() {
+        assert!(matches!(
+            parse(&[[1, 1], [2, 2]]),
+            Err(LevelError::SpawnBlocked { slot: 1, position: [2, 2] })
+        ));
+        assert!(matches!(
+            parse(&[[0, 0], [5, 3]]),
+            Err(LevelError::SpawnBlocked { slot: 0, position: [0, 0] })
+        ));
+    }
+
+    #[test]
+    fn rejects_spawns_sharing_a_tile() {
+        assert!(matches!(
+            parse(&[[1, 1], [5, 3], [1, 1]]),
+            Err(LevelError::SpawnShared { slot: 2, other: 0, position: [1, 1] })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_pickup_out_of_bounds() {
+        let source = source(&[([1, 1], "down"), ([5, 3], "up")], "[[3, 1], [3, -1]]");
+        assert!(matches!(
+            Level::from_toml_str(&source),
+            Err(LevelError::PickupOutOfBounds { position: [3, -1] })
+        ));
+    }
+
+    #[test]
+    fn rejects_an_unknown_tile() {
+        let source = source(&[([1, 1], "down"), ([5, 3], "up")], "[]");
+        let source = source.replace("X.CNC.X", "X.CQC.X");
+        assert!(matches!(
+            Level::from_toml_str(&source),
+            Err(LevelError::UnknownTile { row: 2, column: 3, tile: 'Q' })
+        ));
+    }
+}
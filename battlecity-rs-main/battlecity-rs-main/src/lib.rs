@@ -0,0 +1,28 @@
+//! Battle City simulation core.
+//!
+//! Everything needed to run a match (players, walls, bullets, pickups and the
+//! bots) lives here without any windowing or graphics dependency, so servers,
+//! tools and tests can drive a `Game` headlessly. Frontends such as the raylib
+//! client only read the state back and feed input in.
+
+pub mod animation;
+pub mod bot;
+pub mod camera;
+pub mod classic;
+pub mod connectivity;
+pub mod constants;
+pub mod effect;
+pub mod event;
+pub mod game;
+pub mod input;
+pub mod level;
+pub mod mapgen;
+pub mod nav;
+pub mod pickup;
+pub mod player;
+pub mod projectile;
+pub mod render;
+pub mod rng;
+pub mod rules;
+pub mod transform;
+pub mod wall;
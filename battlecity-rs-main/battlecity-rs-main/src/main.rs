@@ -1,20 +1,48 @@
 use raylib::prelude::*;
 
-mod animation;
-mod constants;
-mod camera;
-mod game;
-mod pickup;
-mod player;
-mod projectile;
-mod render;
-mod transform;
-mod wall;
-
-use crate::game::{Game, GameState};
-use crate::render::{draw_scene_3d, SceneTextures};
+mod editor_raylib;
+mod render_raylib;
+
+use battlecity::event::GameEvent;
+use battlecity::game::{Game, GameState};
+use battlecity::level::Level;
+use battlecity::pickup::PickupType;
+use battlecity::rng::{random_seed, seed_from_args};
+use battlecity::rules::GameRules;
+use battlecity::input::{InputAction, PlayerCommand};
+use crate::editor_raylib::{Editor, EditorAction};
+use crate::render_raylib::{draw_scene_3d, KillFeed, SceneTextures};
 use raylib::prelude::{KeyboardKey, Vector2};
 
+// where the editor saves when no --level file was given
+const DEFAULT_EDITOR_PATH: &str = "resources/levels/custom.toml";
+
+// raylib key -> (player id, action). Only player 0 is human on this client,
+// the other tanks are driven by the bots.
+const KEY_BINDINGS: [(KeyboardKey, u32, InputAction); 5] = [
+    (KeyboardKey::KEY_UP,    0, InputAction::MoveUp),
+    (KeyboardKey::KEY_RIGHT, 0, InputAction::MoveRight),
+    (KeyboardKey::KEY_DOWN,  0, InputAction::MoveDown),
+    (KeyboardKey::KEY_LEFT,  0, InputAction::MoveLeft),
+    (KeyboardKey::KEY_SPACE, 0, InputAction::Fire),
+];
+
+fn poll_input(rl: &RaylibHandle, game: &mut Game) {
+    // several keys may share an action, it is held if any of them is down
+    let mut held: Vec<(u32, InputAction, bool)> = vec![];
+    for (key, player_id, action) in KEY_BINDINGS {
+        let down = rl.is_key_down(key);
+        match held.iter_mut().find(|(id, a, _)| *id == player_id && *a == action) {
+            Some(entry) => entry.2 |= down,
+            None => held.push((player_id, action, down)),
+        }
+    }
+
+    for (player_id, action, pressed) in held {
+        game.apply_command(PlayerCommand { player_id, action, pressed });
+    }
+}
+
 fn main() {
     // --- Raylib window/context ---
     let (mut rl, thread) = raylib::init()
@@ -31,8 +59,45 @@ fn main() {
         .expect("failed to load resources/tanks.png");
     let tex = SceneTextures::new(atlas);
 
+    // --- Audio ---
+    // optional: without a sound device or the wav the game just plays muted
+    let audio = RaylibAudio::init_audio_device()
+        .map_err(|err| eprintln!("no audio: {}", err))
+        .ok();
+    let shot_sound = audio.as_ref().and_then(|audio| {
+        audio
+            .new_sound("resources/gun-shots-230534.wav")
+            .map_err(|err| eprintln!("failed to load resources/gun-shots-230534.wav: {}", err))
+            .ok()
+    });
+    let mut kill_feed = KillFeed::default();
+
     // --- Create game ---
-    let mut game = Game::new_default();
+    let rules = GameRules::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let seed = seed_from_args(std::env::args().skip(1)).unwrap_or_else(random_seed);
+    let mut game = Game::new(rules, seed);
+    match Level::from_args(std::env::args().skip(1)) {
+        Ok(Some(level)) => game.load_level(level),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+    println!("seed: {}", game.seed());
+
+    // the editor works on the --level file, or a new one
+    let editor_path = std::env::args()
+        .skip_while(|arg| arg != "--level")
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_EDITOR_PATH.to_string());
+    let mut editor: Option<Editor> = None;
+    let mut editing = false;
+    // bot slot the menu's D / B keys change
+    let mut menu_bot = 1;
 
     // --- Build Camera3D from game state ---
     let gc0 = game.camera();
@@ -47,11 +112,48 @@ fn main() {
     while !rl.window_should_close() {
         let dt = rl.get_frame_time();
 
+        // --------- EDITOR ---------
+        // F2 opens it from the menu, and goes back to it after a test-play
+        if !editing
+            && rl.is_key_pressed(KeyboardKey::KEY_F2)
+            && (game.state() == GameState::Menu || editor.is_some())
+        {
+            // first time: start from whatever map is loaded right now
+            editor.get_or_insert_with(|| Editor::new(game.current_level(), editor_path.clone().into()));
+            game.reset();
+            editing = true;
+        } else if editing {
+            let action = editor.as_mut().map_or(EditorAction::Close, |e| e.update(&rl, dt));
+            match action {
+                EditorAction::None => {}
+                EditorAction::TestPlay(level) => {
+                    game.load_level(level);
+                    game.start_match();
+                    editing = false;
+                }
+                EditorAction::Close => editing = false,
+            }
+
+            if editing {
+                let mut d2 = rl.begin_drawing(&thread);
+                if let Some(editor) = &editor {
+                    editor.draw(&mut d2);
+                }
+                continue;
+            }
+        }
+
         // --------- INPUT (must be BEFORE begin_drawing) ---------
         // Global hotkeys
         let pressed_r      = rl.is_key_pressed(KeyboardKey::KEY_R);
         let pressed_enter  = rl.is_key_pressed(KeyboardKey::KEY_ENTER);
          let pressed_e = rl.is_key_pressed(KeyboardKey::KEY_E);
+        let pressed_p      = rl.is_key_pressed(KeyboardKey::KEY_P);
+        let pressed_g      = rl.is_key_pressed(KeyboardKey::KEY_G);
+        let pressed_tab    = rl.is_key_pressed(KeyboardKey::KEY_TAB);
+        let pressed_d      = rl.is_key_pressed(KeyboardKey::KEY_D);
+        let pressed_b      = rl.is_key_pressed(KeyboardKey::KEY_B);
+        let pressed_h      = rl.is_key_pressed(KeyboardKey::KEY_H);
 
         if pressed_r {
             // full fresh start -> back to menu (Game::reset sets state = Menu)
@@ -64,16 +166,63 @@ fn main() {
 
 
         // feed movement/fire input for human
-        game.poll_input_raylib(&rl);
+        poll_input(&rl, &mut game);
+
+        // Enter starts a match from the menu, or a new one once it is over
+        if pressed_enter && matches!(game.state(), GameState::Menu | GameState::MatchOver) {
+            game.start_match(); // round 1 countdown
+        }
+
+        if pressed_p {
+            game.toggle_pause();
+        }
+
+        // G cycles the random map generator while in the menu
+        if pressed_g && game.state() == GameState::Menu {
+            game.set_map_generator(game.rules().map_generator.next());
+        }
+
+        // H hands player 1 to the bots too, to watch them play; again to take it back
+        if pressed_h && game.state() == GameState::Menu {
+            game.set_humans(if game.rules().humans == 0 { 1 } else { 0 });
+        }
 
-        // if we are in the menu and user hits Enter, start the game
-        if game.state() == GameState::Menu && pressed_enter {
-            game.resume(); // sets state = Playing, resets state_time
+        // Tab picks a bot, D and B cycle its difficulty and personality
+        if game.state() == GameState::Menu {
+            if pressed_tab {
+                menu_bot = game.next_bot_slot(menu_bot).unwrap_or(menu_bot);
+            }
+            if let Some(config) = game.bot_config(menu_bot).filter(|_| pressed_d) {
+                game.set_bot_config(menu_bot, config.next_difficulty());
+            }
+            if let Some(config) = game.bot_config(menu_bot).filter(|_| pressed_b) {
+                game.set_bot_config(menu_bot, config.next_personality());
+            }
         }
 
         // --------- UPDATE ---------
         game.update_dt(dt);
 
+        // --------- EVENTS ---------
+        kill_feed.on_frame(dt);
+        for event in game.drain_events() {
+            match event {
+                GameEvent::BulletFired { .. } => {
+                    if let Some(sound) = &shot_sound {
+                        sound.play();
+                    }
+                }
+                GameEvent::TankDestroyed { killer, victim, .. } => kill_feed.push_kill(killer, victim),
+                GameEvent::PickupCollected { player, pickup, .. } => {
+                    // health and armor show in the HUD bars already
+                    if !matches!(pickup, PickupType::Health | PickupType::Armor) {
+                        kill_feed.push_pickup(player, pickup);
+                    }
+                }
+                _ => {}
+            }
+        }
+
         // sync camera from game camera
         let gcam = game.camera();
         cam.position = Vector3::new(gcam.eye.x, gcam.eye.y, gcam.eye.z);
@@ -87,7 +236,7 @@ fn main() {
         match game.state() {
             GameState::Menu => {
                 d2.clear_background(Color::BLACK);
-                render::draw_menu(&mut d2);
+                render_raylib::draw_menu(&mut d2, &game, menu_bot);
             }
 
             _ => {
@@ -100,9 +249,10 @@ fn main() {
                 }
 
                 // 2D overlays
-                render::draw_minimap_2d(&mut d2, &game, Vector2::new(10.0, 40.0), 8);
-                render::draw_hud_bar(&mut d2, &game);
-                render::draw_state_banner_2d(&mut d2, &game);
+                render_raylib::draw_minimap_2d(&mut d2, &game, Vector2::new(10.0, 40.0), 8);
+                render_raylib::draw_hud_bar(&mut d2, &game);
+                render_raylib::draw_kill_feed(&mut d2, &kill_feed);
+                render_raylib::draw_state_banner_2d(&mut d2, &game);
                 d2.draw_fps(10, 10);
             }
         }
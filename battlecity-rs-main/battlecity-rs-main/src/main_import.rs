@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+use std::process::exit;
+
+use battlecity::classic::ClassicStage;
+
+// Converts a collection of classic NES stages into level files:
+//
+//   battlecity-import resources/levels/classic/stages.txt resources/levels/classic
+//
+// writes stage01.toml, stage02.toml, .. next to each other in the output directory.
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let [input, output] = args.as_slice() else {
+        eprintln!("usage: battlecity-import <stages.txt> <output dir>");
+        exit(2);
+    };
+
+    let source = std::fs::read_to_string(input).unwrap_or_else(|err| {
+        eprintln!("cannot read {}: {}", input, err);
+        exit(1);
+    });
+    let stages = ClassicStage::parse_all(&source).unwrap_or_else(|err| {
+        eprintln!("{}: {}", input, err);
+        exit(1);
+    });
+
+    let output = PathBuf::from(output);
+    if let Err(err) = std::fs::create_dir_all(&output) {
+        eprintln!("cannot create {}: {}", output.display(), err);
+        exit(1);
+    }
+
+    for (index, stage) in stages.iter().enumerate() {
+        let level = stage.to_level();
+        if let Err(err) = level.validate() {
+            eprintln!("stage '{}' does not make a playable level: {}", stage.name, err);
+            exit(1);
+        }
+
+        let path = output.join(format!("stage{:02}.toml", index + 1));
+        if let Err(err) = level.save(&path) {
+            eprintln!("cannot write {}: {}", path.display(), err);
+            exit(1);
+        }
+        println!("{} -> {}", stage.name, path.display());
+    }
+}
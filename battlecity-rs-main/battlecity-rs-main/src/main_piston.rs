@@ -0,0 +1,138 @@
+use glutin_window::GlutinWindow;
+use opengl_graphics::{Filter, GlGraphics, GlyphCache, OpenGL, Texture, TextureSettings};
+use piston::event_loop::{EventSettings, Events};
+use piston::input::{Button, Key, PressEvent, ReleaseEvent, RenderEvent, UpdateEvent};
+use piston::window::WindowSettings;
+
+mod render_piston;
+
+use battlecity::constants::{
+    DEFAULT_CELL_SIZE, GAME_OVER_TEXTURE_PATH, SCOREBOARD_HEIGHT, TANKS_TEXTURE_PATH,
+};
+use battlecity::game::{Game, GameState};
+use battlecity::level::Level;
+use battlecity::rng::{random_seed, seed_from_args};
+use battlecity::rules::GameRules;
+use battlecity::input::InputAction;
+use crate::render_piston::{draw_game, PistonTextures};
+
+const FONT_PATH: &str = "resources/Verdana.ttf";
+
+// piston key -> (player id, action). Only player 0 is human on this client,
+// the other tanks are driven by the bots.
+const KEY_BINDINGS: [(Key, u32, InputAction); 5] = [
+    (Key::Up,    0, InputAction::MoveUp),
+    (Key::Right, 0, InputAction::MoveRight),
+    (Key::Down,  0, InputAction::MoveDown),
+    (Key::Left,  0, InputAction::MoveLeft),
+    (Key::Space, 0, InputAction::Fire),
+];
+
+fn on_key(game: &mut Game, key: Key, pressed: bool) {
+    for (bound, player_id, action) in KEY_BINDINGS {
+        if bound != key {
+            continue;
+        }
+        if let Some(player) = game.players_mut().iter_mut().find(|p| p.get_id() == player_id) {
+            if pressed { player.on_press(action) } else { player.on_release(action) }
+        }
+    }
+}
+
+fn main() {
+    let rules = GameRules::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    let level = Level::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+
+    // a level brings its own map size
+    let (columns, rows) = level
+        .as_ref()
+        .map_or((rules.column_count, rules.row_count), |level| (level.width, level.height));
+
+    let opengl = OpenGL::V3_2;
+    let size = [
+        columns as f64 * DEFAULT_CELL_SIZE,
+        rows as f64 * DEFAULT_CELL_SIZE + SCOREBOARD_HEIGHT,
+    ];
+
+    let mut window: GlutinWindow = WindowSettings::new("Battle Kill", size)
+        .graphics_api(opengl)
+        .exit_on_esc(true)
+        .resizable(false)
+        .build()
+        .expect("failed to create piston window");
+    let mut gl = GlGraphics::new(opengl);
+
+    let settings = TextureSettings::new().filter(Filter::Nearest);
+    let tex = PistonTextures {
+        atlas: Texture::from_path(TANKS_TEXTURE_PATH, &settings)
+            .expect("failed to load resources/tanks.png"),
+        game_over: Texture::from_path(GAME_OVER_TEXTURE_PATH, &settings)
+            .expect("failed to load resources/gameover.png"),
+    };
+    let mut glyphs = GlyphCache::new(FONT_PATH, (), TextureSettings::new())
+        .expect("failed to load resources/Verdana.ttf");
+
+    let seed = seed_from_args(std::env::args().skip(1)).unwrap_or_else(random_seed);
+    let mut game = Game::new(rules, seed);
+    if let Some(level) = level {
+        game.load_level(level);
+    }
+    println!("seed: {}", game.seed());
+
+    // bot slot the menu's D / B keys change
+    let mut menu_bot = 1;
+
+    let mut events = Events::new(EventSettings::new());
+    while let Some(e) = events.next(&mut window) {
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            match key {
+                Key::R => game.reset(),
+                Key::Return if matches!(game.state(), GameState::Menu | GameState::MatchOver) => {
+                    game.start_match()
+                }
+                Key::P => game.toggle_pause(),
+                Key::G if game.state() == GameState::Menu => {
+                    game.set_map_generator(game.rules().map_generator.next())
+                }
+                // watch the bots play player 1 as well, or take it back
+                Key::H if game.state() == GameState::Menu => {
+                    game.set_humans(if game.rules().humans == 0 { 1 } else { 0 })
+                }
+                Key::Tab if game.state() == GameState::Menu => {
+                    menu_bot = game.next_bot_slot(menu_bot).unwrap_or(menu_bot)
+                }
+                Key::D | Key::B if game.state() == GameState::Menu => {
+                    if let Some(config) = game.bot_config(menu_bot) {
+                        let config = match key {
+                            Key::D => config.next_difficulty(),
+                            _ => config.next_personality(),
+                        };
+                        game.set_bot_config(menu_bot, config);
+                    }
+                }
+                _ => on_key(&mut game, key, true),
+            }
+        }
+
+        if let Some(Button::Keyboard(key)) = e.release_args() {
+            on_key(&mut game, key, false);
+        }
+
+        if let Some(args) = e.update_args() {
+            game.update_dt(args.dt as f32);
+            // no audio or kill feed in this client, just keep the queue empty
+            game.drain_events().for_each(drop);
+        }
+
+        if let Some(args) = e.render_args() {
+            gl.draw(args.viewport(), |c, g| draw_game(&game, &tex, &mut glyphs, menu_bot, c, g));
+        }
+    }
+}
@@ -0,0 +1,440 @@
+//! Procedural map generators for matches without a level file.
+//!
+//! Every generator fills a `column_count` x `row_count` grid from the game's
+//! RNG, so the seed reproduces the map, and frames it with a border. Which
+//! one runs is `GameRules::map_generator`; `GameRules::wall_density`,
+//! `concrete_ratio` and `net_ratio` shape what they place.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rng::GameRng,
+    rules::GameRules,
+    wall::{Wall, WallType},
+};
+
+type Grid = Vec<Vec<WallType>>;
+
+pub trait MapGenerator {
+    fn name(&self) -> &'static str;
+
+    /// Builds a map of `rules.column_count` x `rules.row_count` tiles, `walls[y][x]`.
+    fn generate(&self, rules: &GameRules, rng: &mut GameRng) -> Vec<Vec<Wall>>;
+}
+
+/// The generators a rules file or the menu can pick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapGeneratorKind {
+    /// Independent random tiles, the original look.
+    #[default]
+    Noise,
+    Symmetric,
+    Caves,
+    Rooms,
+    Maze,
+}
+
+impl MapGeneratorKind {
+    pub const ALL: [MapGeneratorKind; 5] = [
+        MapGeneratorKind::Noise,
+        MapGeneratorKind::Symmetric,
+        MapGeneratorKind::Caves,
+        MapGeneratorKind::Rooms,
+        MapGeneratorKind::Maze,
+    ];
+
+    pub fn generator(self) -> Box<dyn MapGenerator> {
+        match self {
+            MapGeneratorKind::Noise => Box::new(NoiseGenerator),
+            MapGeneratorKind::Symmetric => Box::new(SymmetricGenerator),
+            MapGeneratorKind::Caves => Box::new(CavesGenerator),
+            MapGeneratorKind::Rooms => Box::new(RoomsGenerator),
+            MapGeneratorKind::Maze => Box::new(MazeGenerator),
+        }
+    }
+
+    /// The one after this in `ALL`, wrapping around; for cycling in menus.
+    pub fn next(self) -> MapGeneratorKind {
+        let index = Self::ALL.iter().position(|&kind| kind == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+// a wall tile of the kind the ratios ask for
+fn wall_tile(rules: &GameRules, rng: &mut GameRng) -> WallType {
+    let roll = rng.gen::<f64>();
+    if roll < rules.concrete_ratio {
+        WallType::Concrete
+    } else if roll < rules.concrete_ratio + rules.net_ratio {
+        WallType::Net
+    } else {
+        WallType::Brick
+    }
+}
+
+fn random_tile(rules: &GameRules, rng: &mut GameRng) -> WallType {
+    if rng.gen_bool(rules.wall_density) {
+        wall_tile(rules, rng)
+    } else {
+        WallType::Empty
+    }
+}
+
+fn into_walls(mut grid: Grid) -> Vec<Vec<Wall>> {
+    let height = grid.len();
+    let width = grid.first().map_or(0, |row| row.len());
+
+    for (y, row) in grid.iter_mut().enumerate() {
+        for (x, tile) in row.iter_mut().enumerate() {
+            if x == 0 || y == 0 || x + 1 == width || y + 1 == height {
+                *tile = WallType::Border;
+            }
+        }
+    }
+
+    grid.into_iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.into_iter()
+                .enumerate()
+                .map(|(x, tile)| Wall::new([x as i32, y as i32]).with_variant(tile))
+                .collect()
+        })
+        .collect()
+}
+
+fn size(rules: &GameRules) -> (usize, usize) {
+    (rules.column_count as usize, rules.row_count as usize)
+}
+
+pub struct NoiseGenerator;
+
+impl MapGenerator for NoiseGenerator {
+    fn name(&self) -> &'static str { "Noise" }
+
+    fn generate(&self, rules: &GameRules, rng: &mut GameRng) -> Vec<Vec<Wall>> {
+        let (width, height) = size(rules);
+        let grid = (0..height)
+            .map(|_| (0..width).map(|_| random_tile(rules, rng)).collect())
+            .collect();
+        into_walls(grid)
+    }
+}
+
+/// Noise in the top left quarter, mirrored onto the other three, so each
+/// corner spawn sees the same map.
+pub struct SymmetricGenerator;
+
+impl MapGenerator for SymmetricGenerator {
+    fn name(&self) -> &'static str { "Symmetric" }
+
+    fn generate(&self, rules: &GameRules, rng: &mut GameRng) -> Vec<Vec<Wall>> {
+        let (width, height) = size(rules);
+        let mut grid = vec![vec![WallType::Empty; width]; height];
+
+        for y in 0..height.div_ceil(2) {
+            for x in 0..width.div_ceil(2) {
+                let tile = random_tile(rules, rng);
+                let (mx, my) = (width - 1 - x, height - 1 - y);
+                grid[y][x] = tile;
+                grid[y][mx] = tile;
+                grid[my][x] = tile;
+                grid[my][mx] = tile;
+            }
+        }
+        into_walls(grid)
+    }
+}
+
+/// Cellular automaton smoothing of noise into open caves with thick walls.
+pub struct CavesGenerator;
+
+const CAVE_STEPS: usize = 4;
+
+impl MapGenerator for CavesGenerator {
+    fn name(&self) -> &'static str { "Caves" }
+
+    fn generate(&self, rules: &GameRules, rng: &mut GameRng) -> Vec<Vec<Wall>> {
+        let (width, height) = size(rules);
+
+        // the automaton thickens walls, so seed it sparser than the density;
+        // past ~0.55 every cave closes up
+        let fill = (rules.wall_density * 0.7).min(0.55);
+        let mut solid = (0..height)
+            .map(|_| (0..width).map(|_| rng.gen_bool(fill)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        for _ in 0..CAVE_STEPS {
+            let before = solid.clone();
+            for (y, row) in solid.iter_mut().enumerate() {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    // off-map counts as rock so caves stay off the edges
+                    let neighbours = (-1i32..=1)
+                        .flat_map(|dy| (-1i32..=1).map(move |dx| (dx, dy)))
+                        .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+                        .filter(|&(dx, dy)| {
+                            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                            nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32
+                                || before[ny as usize][nx as usize]
+                        })
+                        .count();
+                    if neighbours >= 5 {
+                        *cell = true;
+                    } else if neighbours <= 3 {
+                        *cell = false;
+                    }
+                }
+            }
+        }
+
+        let grid = solid
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|is_solid| if is_solid { wall_tile(rules, rng) } else { WallType::Empty })
+                    .collect()
+            })
+            .collect();
+        into_walls(grid)
+    }
+}
+
+/// Binary space partition into rooms joined by corridors, with a little
+/// brick cover scattered in the rooms.
+pub struct RoomsGenerator;
+
+const MIN_LEAF: usize = 6;
+
+#[derive(Clone, Copy)]
+struct Rect {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+impl Rect {
+    fn center(&self) -> (usize, usize) {
+        (self.x + self.w / 2, self.y + self.h / 2)
+    }
+}
+
+impl RoomsGenerator {
+    // splits `area` down to leaves, carves a room in each and returns the
+    // room whose centre stands for this subtree when corridors are dug
+    fn split(area: Rect, grid: &mut Grid, rng: &mut GameRng) -> Rect {
+        let can_split_w = area.w >= MIN_LEAF * 2;
+        let can_split_h = area.h >= MIN_LEAF * 2;
+
+        if !can_split_w && !can_split_h {
+            let w = rng.gen_range((area.w / 2).max(2)..=(area.w - 2).max(2));
+            let h = rng.gen_range((area.h / 2).max(2)..=(area.h - 2).max(2));
+            let x = area.x + rng.gen_range(1..=(area.w - w).max(1));
+            let y = area.y + rng.gen_range(1..=(area.h - h).max(1));
+            let room = Rect { x, y, w: w.min(area.w - 1), h: h.min(area.h - 1) };
+            Self::carve(room, grid);
+            return room;
+        }
+
+        let vertical = if can_split_w && can_split_h { area.w >= area.h } else { can_split_w };
+        let (a, b) = if vertical {
+            let cut = rng.gen_range(MIN_LEAF..=(area.w - MIN_LEAF));
+            (Rect { w: cut, ..area }, Rect { x: area.x + cut, w: area.w - cut, ..area })
+        } else {
+            let cut = rng.gen_range(MIN_LEAF..=(area.h - MIN_LEAF));
+            (Rect { h: cut, ..area }, Rect { y: area.y + cut, h: area.h - cut, ..area })
+        };
+
+        let left = Self::split(a, grid, rng);
+        let right = Self::split(b, grid, rng);
+        Self::corridor(left.center(), right.center(), grid, rng);
+        if rng.gen_bool(0.5) { left } else { right }
+    }
+
+    fn carve(room: Rect, grid: &mut Grid) {
+        for row in grid.iter_mut().skip(room.y).take(room.h) {
+            for tile in row.iter_mut().skip(room.x).take(room.w) {
+                *tile = WallType::Empty;
+            }
+        }
+    }
+
+    fn corridor(from: (usize, usize), to: (usize, usize), grid: &mut Grid, rng: &mut GameRng) {
+        let ((x0, y0), (x1, y1)) = (from, to);
+        // one L-shaped corridor, bending either way
+        let corner = if rng.gen_bool(0.5) { (x1, y0) } else { (x0, y1) };
+        for (a, b) in [((x0, y0), corner), (corner, (x1, y1))] {
+            let (x, y) = (a.0.min(b.0), a.1.min(b.1));
+            let segment = Rect { x, y, w: a.0.max(b.0) - x + 1, h: a.1.max(b.1) - y + 1 };
+            Self::carve(segment, grid);
+        }
+    }
+}
+
+impl MapGenerator for RoomsGenerator {
+    fn name(&self) -> &'static str { "Rooms" }
+
+    fn generate(&self, rules: &GameRules, rng: &mut GameRng) -> Vec<Vec<Wall>> {
+        let (width, height) = size(rules);
+        let mut grid = (0..height)
+            .map(|_| (0..width).map(|_| wall_tile(rules, rng)).collect())
+            .collect::<Grid>();
+
+        Self::split(Rect { x: 0, y: 0, w: width, h: height }, &mut grid, rng);
+
+        // cover inside the rooms, sparser than the density asks for
+        for tile in grid.iter_mut().flatten() {
+            if *tile == WallType::Empty && rng.gen_bool(rules.wall_density * 0.15) {
+                *tile = WallType::Brick;
+            }
+        }
+        into_walls(grid)
+    }
+}
+
+/// Depth first maze on the odd tiles, then some walls knocked out so there
+/// is more than one way around. Denser rules keep more of the maze.
+pub struct MazeGenerator;
+
+impl MapGenerator for MazeGenerator {
+    fn name(&self) -> &'static str { "Maze" }
+
+    fn generate(&self, rules: &GameRules, rng: &mut GameRng) -> Vec<Vec<Wall>> {
+        let (width, height) = size(rules);
+        let mut grid = (0..height)
+            .map(|_| (0..width).map(|_| wall_tile(rules, rng)).collect())
+            .collect::<Grid>();
+
+        let (cells_x, cells_y) = ((width - 1) / 2, (height - 1) / 2);
+        let mut visited = vec![vec![false; cells_x]; cells_y];
+        let tile_of = |cx: usize, cy: usize| (cx * 2 + 1, cy * 2 + 1);
+
+        let mut stack = vec![(0usize, 0usize)];
+        visited[0][0] = true;
+        let (x, y) = tile_of(0, 0);
+        grid[y][x] = WallType::Empty;
+
+        while let Some(&(cx, cy)) = stack.last() {
+            let options = [(0i32, -1i32), (1, 0), (0, 1), (-1, 0)]
+                .into_iter()
+                .map(|(dx, dy)| (cx as i32 + dx, cy as i32 + dy))
+                .filter(|&(nx, ny)| {
+                    nx >= 0 && ny >= 0 && (nx as usize) < cells_x && (ny as usize) < cells_y
+                        && !visited[ny as usize][nx as usize]
+                })
+                .collect::<Vec<_>>();
+
+            if options.is_empty() {
+                stack.pop();
+                continue;
+            }
+
+            let (nx, ny) = options[rng.gen_range(0..options.len())];
+            let (nx, ny) = (nx as usize, ny as usize);
+            visited[ny][nx] = true;
+
+            let (ax, ay) = tile_of(cx, cy);
+            let (bx, by) = tile_of(nx, ny);
+            grid[(ay + by) / 2][(ax + bx) / 2] = WallType::Empty;
+            grid[by][bx] = WallType::Empty;
+            stack.push((nx, ny));
+        }
+
+        // loops: open walls that sit between two corridors
+        let open_chance = (1.0 - rules.wall_density) * 0.5;
+        for y in 1..height.saturating_sub(1) {
+            for x in 1..width.saturating_sub(1) {
+                if grid[y][x] == WallType::Empty {
+                    continue;
+                }
+                let between_x = grid[y][x - 1] == WallType::Empty && grid[y][x + 1] == WallType::Empty;
+                let between_y = grid[y - 1][x] == WallType::Empty && grid[y + 1][x] == WallType::Empty;
+                if (between_x || between_y) && rng.gen_bool(open_chance) {
+                    grid[y][x] = WallType::Empty;
+                }
+            }
+        }
+        into_walls(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{render::GameRenderObject, rng::rng_from_seed};
+
+    fn rules(column_count: u8, row_count: u8) -> GameRules {
+        GameRules { column_count, row_count, ..GameRules::default() }
+    }
+
+    fn tiles(kind: MapGeneratorKind, rules: &GameRules, seed: u64) -> Grid {
+        let walls = kind.generator().generate(rules, &mut rng_from_seed(seed));
+        walls.iter().map(|row| row.iter().map(Wall::variant).collect()).collect()
+    }
+
+    #[test]
+    fn next_cycles_through_all() {
+        let mut kind = MapGeneratorKind::default();
+        for expected in MapGeneratorKind::ALL.iter().cycle().skip(1).take(MapGeneratorKind::ALL.len()) {
+            kind = kind.next();
+            assert_eq!(kind, *expected);
+        }
+        assert_eq!(kind, MapGeneratorKind::default());
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        let rules = GameRules::default();
+        for kind in MapGeneratorKind::ALL {
+            assert_eq!(tiles(kind, &rules, 7), tiles(kind, &rules, 7), "{:?}", kind);
+            assert_ne!(tiles(kind, &rules, 7), tiles(kind, &rules, 8), "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn fills_the_rules_size_inside_a_border() {
+        // the 5x5 minimum, odd and even sizes and the default
+        for (width, height) in [(5, 5), (5, 9), (8, 5), (31, 21), (30, 20)] {
+            let rules = rules(width, height);
+            for kind in MapGeneratorKind::ALL {
+                for seed in 0..20 {
+                    let grid = tiles(kind, &rules, seed);
+                    assert_eq!(grid.len(), height as usize, "{:?} {}x{}", kind, width, height);
+                    for (y, row) in grid.iter().enumerate() {
+                        assert_eq!(row.len(), width as usize, "{:?} {}x{}", kind, width, height);
+                        for (x, &tile) in row.iter().enumerate() {
+                            let edge = x == 0 || y == 0 || x + 1 == row.len() || y + 1 == grid.len();
+                            assert_eq!(tile == WallType::Border, edge, "{:?} at {}, {}", kind, x, y);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn walls_know_their_tile() {
+        let walls = NoiseGenerator.generate(&GameRules::default(), &mut rng_from_seed(1));
+        for (y, row) in walls.iter().enumerate() {
+            for (x, wall) in row.iter().enumerate() {
+                assert_eq!(*wall.get_position(), [x as i32, y as i32]);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_mirrors_both_ways() {
+        for (width, height) in [(30, 20), (31, 21), (5, 5)] {
+            let grid = tiles(MapGeneratorKind::Symmetric, &rules(width, height), 3);
+            let (width, height) = (width as usize, height as usize);
+            for y in 0..height {
+                for x in 0..width {
+                    assert_eq!(grid[y][x], grid[y][width - 1 - x]);
+                    assert_eq!(grid[y][x], grid[height - 1 - y][x]);
+                }
+            }
+        }
+    }
+}
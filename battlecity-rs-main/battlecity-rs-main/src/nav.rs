@@ -0,0 +1,273 @@
+//! Grid pathfinding for tanks.
+//!
+//! A* over the wall grid under the same rules tanks drive by: open tiles cost
+//! one step, destructible ones (bricks) cost the shots it takes to clear them,
+//! and nothing gets past concrete, water or the border. The bots use it to
+//! find their way to a target; `connectivity` prices tiles the same way.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::wall::Wall;
+
+/// Cost of entering an open tile.
+pub const OPEN_COST: u32 = 1;
+/// A brick has to be shot first, so each hit it takes is a detour worth a few open tiles.
+pub const BRICK_HIT_COST: u32 = 4;
+
+/// What entering `wall` costs, `None` when a tank can never get there.
+pub fn enter_cost(wall: &Wall) -> Option<u32> {
+    enter_cost_with(wall, BRICK_HIT_COST)
+}
+
+fn enter_cost_with(wall: &Wall, brick_hit_cost: u32) -> Option<u32> {
+    let flags = wall.flags();
+    if !flags.blocks_tanks {
+        Some(OPEN_COST)
+    } else if flags.is_destructible() {
+        Some(brick_hit_cost * wall.get_hit_points().max(1))
+    } else {
+        None
+    }
+}
+
+/// A way from one tile to another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    /// Tiles to enter in order, the start not included, the goal last.
+    pub tiles: Vec<[i32; 2]>,
+    pub cost: u32,
+}
+
+impl Path {
+    /// Tile to enter next, `None` once there.
+    pub fn next(&self) -> Option<[i32; 2]> {
+        self.tiles.first().copied()
+    }
+
+    pub fn goal(&self) -> Option<[i32; 2]> {
+        self.tiles.last().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Drops every tile up to `position` if it is on the path; false when it
+    /// is not, i.e. whoever follows the path got pushed off it.
+    pub fn advance(&mut self, position: [i32; 2]) -> bool {
+        match self.tiles.iter().position(|&tile| tile == position) {
+            Some(index) => {
+                self.tiles.drain(..=index);
+                true
+            }
+            None => self.next().is_some_and(|next| is_adjacent(position, next)),
+        }
+    }
+}
+
+fn is_adjacent(a: [i32; 2], b: [i32; 2]) -> bool {
+    (a[0] - b[0]).abs() + (a[1] - b[1]).abs() == 1
+}
+
+/// Cheapest way from `from` to `to` on `walls` (`walls[y][x]`), `None` when
+/// the goal is out of reach or off the map.
+pub fn find_path(walls: &[Vec<Wall>], from: [i32; 2], to: [i32; 2]) -> Option<Path> {
+    find_path_with(walls, from, to, BRICK_HIT_COST)
+}
+
+/// Like `find_path`, with each hit a brick takes costing `brick_hit_cost`
+/// instead; the lower, the sooner bricks are shot through rather than driven around.
+pub fn find_path_with(
+    walls: &[Vec<Wall>],
+    from: [i32; 2],
+    to: [i32; 2],
+    brick_hit_cost: u32,
+) -> Option<Path> {
+    let height = walls.len();
+    let width = walls.first().map_or(0, |row| row.len());
+    let index_of = |[x, y]: [i32; 2]| {
+        (x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height)
+            .then(|| y as usize * width + x as usize)
+    };
+    let tile_of = |index: usize| [(index % width) as i32, (index / width) as i32];
+
+    let start = index_of(from)?;
+    let goal = index_of(to)?;
+    // manhattan distance never overestimates, every step costs at least OPEN_COST
+    let estimate = |index: usize| {
+        let [x, y] = tile_of(index);
+        ((x - to[0]).unsigned_abs() + (y - to[1]).unsigned_abs()) * OPEN_COST
+    };
+
+    let mut cost = vec![u32::MAX; width * height];
+    let mut previous = vec![None; width * height];
+    cost[start] = 0;
+    let mut queue = BinaryHeap::from([Reverse((estimate(start), start))]);
+
+    while let Some(Reverse((_, index))) = queue.pop() {
+        if index == goal {
+            let mut tiles = vec![];
+            let mut current = goal;
+            while let Some(before) = previous[current] {
+                tiles.push(tile_of(current));
+                current = before;
+            }
+            tiles.reverse();
+            return Some(Path { tiles, cost: cost[goal] });
+        }
+
+        let (x, y) = (index % width, index / width);
+        let neighbours = [
+            (x > 0).then(|| index - 1),
+            (x + 1 < width).then(|| index + 1),
+            (y > 0).then(|| index - width),
+            (y + 1 < height).then(|| index + width),
+        ];
+
+        for next in neighbours.into_iter().flatten() {
+            let Some(step) = enter_cost_with(&walls[next / width][next % width], brick_hit_cost) else {
+                continue;
+            };
+
+            let next_cost = cost[index] + step;
+            if next_cost < cost[next] {
+                cost[next] = next_cost;
+                previous[next] = Some(index);
+                queue.push(Reverse((next_cost + estimate(next), next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::char_to_tile;
+
+    // `rows` drawn as in level files
+    fn walls(rows: &[&str]) -> Vec<Vec<Wall>> {
+        rows.iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(|(x, tile)| {
+                        Wall::new([x as i32, y as i32]).with_variant(char_to_tile(tile).unwrap())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn brick_cost() -> u32 {
+        BRICK_HIT_COST * Wall::new([0, 0]).brick().get_hit_points()
+    }
+
+    #[test]
+    fn finds_the_way_out_of_a_concrete_pocket() {
+        let walls = walls(&[
+            "XXXXXXXXX",
+            "X.......X",
+            "X.......X",
+            "X.CCCCC.X",
+            "X.C...C.X",
+            "X.C...C.X",
+            "X.......X",
+            "XXXXXXXXX",
+        ]);
+        let path = find_path(&walls, [4, 4], [4, 1]).unwrap();
+
+        assert_eq!(path.goal(), Some([4, 1]));
+        assert_eq!(path.cost, 13);
+        assert_eq!(path.tiles.len(), 13);
+        let mut from = [4, 4];
+        for &tile in &path.tiles {
+            assert!(is_adjacent(from, tile));
+            assert!(!walls[tile[1] as usize][tile[0] as usize].blocks_tanks());
+            from = tile;
+        }
+    }
+
+    const LONG_DETOUR: [&str; 9] = [
+        "XXXXXXXXX",
+        "X...B...X",
+        "X...C...X",
+        "X...C...X",
+        "X...C...X",
+        "X...C...X",
+        "X...C...X",
+        "X.......X",
+        "XXXXXXXXX",
+    ];
+
+    const SHORT_DETOUR: [&str; 5] = [
+        "XXXXXXXXX",
+        "X...B...X",
+        "X...C...X",
+        "X.......X",
+        "XXXXXXXXX",
+    ];
+
+    #[test]
+    fn shoots_through_a_brick_rather_than_drive_far_around() {
+        // around is 14 tiles, through the brick costs less
+        assert!(brick_cost() + OPEN_COST < 14);
+        let path = find_path(&walls(&LONG_DETOUR), [3, 1], [5, 1]).unwrap();
+        assert_eq!(path.tiles, vec![[4, 1], [5, 1]]);
+        assert_eq!(path.cost, brick_cost() + OPEN_COST);
+    }
+
+    #[test]
+    fn drives_around_a_brick_when_that_is_short() {
+        assert!(brick_cost() + OPEN_COST > 6);
+        let path = find_path(&walls(&SHORT_DETOUR), [3, 1], [5, 1]).unwrap();
+        assert_eq!(path.cost, 6);
+        assert!(!path.tiles.contains(&[4, 1]));
+    }
+
+    #[test]
+    fn cheap_bricks_are_shot_through_sooner() {
+        let path = find_path_with(&walls(&SHORT_DETOUR), [3, 1], [5, 1], 1).unwrap();
+        assert_eq!(path.tiles, vec![[4, 1], [5, 1]]);
+    }
+
+    #[test]
+    fn no_path_to_a_sealed_goal() {
+        let walls = walls(&[
+            "XXXXXXX",
+            "X.....X",
+            "X.CCC.X",
+            "X.C.C.X",
+            "X.CCC.X",
+            "X.....X",
+            "XXXXXXX",
+        ]);
+        assert_eq!(find_path(&walls, [1, 1], [3, 3]), None);
+        assert_eq!(find_path(&walls, [1, 1], [0, 3]), None);
+        assert_eq!(find_path(&walls, [1, 1], [2, 2]), None);
+        assert_eq!(find_path(&walls, [1, 1], [9, 1]), None);
+    }
+
+    #[test]
+    fn advance_follows_the_path_until_pushed_off() {
+        let walls = walls(&["XXXXXXX", "X.....X", "X.....X", "XXXXXXX"]);
+        let mut path = find_path(&walls, [1, 1], [5, 1]).unwrap();
+        assert_eq!(path.tiles, vec![[2, 1], [3, 1], [4, 1], [5, 1]]);
+
+        // still at the start, next to the first tile
+        assert!(path.advance([1, 1]));
+        assert_eq!(path.next(), Some([2, 1]));
+
+        assert!(path.advance([3, 1]));
+        assert_eq!(path.tiles, vec![[4, 1], [5, 1]]);
+
+        // shoved a row down
+        assert!(!path.advance([3, 2]));
+
+        assert!(path.advance([5, 1]));
+        assert!(path.is_empty());
+        assert_eq!(path.next(), None);
+    }
+}
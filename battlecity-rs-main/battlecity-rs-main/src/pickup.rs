@@ -1,9 +1,70 @@
-use crate::{render::GameRenderObject, constants::{ARMOR_PICKUP_TILE, HEALTH_PICKUP_TILE}};
+use crate::{
+    render::GameRenderObject,
+    rules::GameRules,
+    constants::{
+        ARMOR_PICKUP_TILE, CLOCK_PICKUP_TILE, GRENADE_PICKUP_TILE, HEALTH_PICKUP_TILE,
+        HELMET_PICKUP_TILE, SHOVEL_PICKUP_TILE, STAR_PICKUP_TILE, TANK_PICKUP_TILE,
+    },
+};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PickupType {
     Health,
     Armor,
+    /// One more shot upgrade tier: faster bullets, then faster reload, then
+    /// bullets that break concrete.
+    Star,
+    /// Walls around the collector's spawn turn to concrete for a while.
+    Shovel,
+    /// Destroys every tank on the map that is not on the collector's team;
+    /// allies and invulnerable tanks are skipped.
+    Grenade,
+    /// Freezes every tank that is not on the collector's team for a while.
+    Clock,
+    /// Invulnerability for a while.
+    Helmet,
+    /// Extra life.
+    Tank,
+}
+
+impl PickupType {
+    pub const ALL: [PickupType; 8] = [
+        PickupType::Health,
+        PickupType::Armor,
+        PickupType::Star,
+        PickupType::Shovel,
+        PickupType::Grenade,
+        PickupType::Clock,
+        PickupType::Helmet,
+        PickupType::Tank,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PickupType::Health => "Health",
+            PickupType::Armor => "Armor",
+            PickupType::Star => "Star",
+            PickupType::Shovel => "Shovel",
+            PickupType::Grenade => "Grenade",
+            PickupType::Clock => "Clock",
+            PickupType::Helmet => "Helmet",
+            PickupType::Tank => "Tank",
+        }
+    }
+
+    /// Seconds between two spawns of this kind.
+    pub fn spawn_time(self, rules: &GameRules) -> f64 {
+        match self {
+            PickupType::Health => rules.health_spawn_time,
+            PickupType::Armor => rules.armor_spawn_time,
+            PickupType::Star => rules.star_spawn_time,
+            PickupType::Shovel => rules.shovel_spawn_time,
+            PickupType::Grenade => rules.grenade_spawn_time,
+            PickupType::Clock => rules.clock_spawn_time,
+            PickupType::Helmet => rules.helmet_spawn_time,
+            PickupType::Tank => rules.tank_spawn_time,
+        }
+    }
 }
 
 pub struct Pickup {
@@ -20,6 +81,12 @@ impl GameRenderObject for Pickup {
         match self.variant {
             PickupType::Health => &HEALTH_PICKUP_TILE,
             PickupType::Armor => &ARMOR_PICKUP_TILE,
+            PickupType::Star => &STAR_PICKUP_TILE,
+            PickupType::Shovel => &SHOVEL_PICKUP_TILE,
+            PickupType::Grenade => &GRENADE_PICKUP_TILE,
+            PickupType::Clock => &CLOCK_PICKUP_TILE,
+            PickupType::Helmet => &HELMET_PICKUP_TILE,
+            PickupType::Tank => &TANK_PICKUP_TILE,
         }
     }
 
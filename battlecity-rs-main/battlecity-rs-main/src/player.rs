@@ -1,20 +1,21 @@
-use piston::Key;
-
 use crate::{
-    constants::{
-        PLAYER_LIVES, PLAYER_MAX_ARMOR, PLAYER_MAX_HEALTH, PLAYER_SHOT_INTERVAL,
-        PLAYER_SPAWN_ARMOR, PLAYER_SPAWN_HEALTH, TANK_1_TILES,
-    },
+    constants::{MAX_STAR_TIER, TANK_1_TILES},
+    effect::{StatusEffectKind, StatusEffects},
+    input::InputAction,
     render::GameRenderObject,
+    rules::GameRules,
     transform::LookDirection,
 };
 
 pub struct Player{
     id: u32,
+    // `None` plays alone against everyone
+    team: Option<u32>,
     // position of jugador 
     position: [[i32; 2]; 2],
 
     lives: u32,
+    spawn_lives: u32,
     health: u32,
     armor: u32,
     max_health: u32 ,
@@ -29,21 +30,27 @@ pub struct Player{
 
     last_shot_dt: f64,
     shot_interval: f64,
+    // stars collected since the last respawn, see `PickupType::Star`
+    star_tier: u32,
+
+    effects: StatusEffects,
+    respawn_delay: f64,
+    respawn_countdown: f64,
 
     
-    movement_controls: [Key; 4],
     movement_controls_state: [bool; 4] ,
-    fire_control: Key,
     fire_control_state: bool,
 
     direction: LookDirection,
     spawn_direction: LookDirection,
+    // set after driving onto ice: the next move is forced this way
+    sliding: Option<LookDirection>,
 
     tiles: [[f64; 4]; 8],
 }
 
 impl GameRenderObject for Player {
-    fn is_visible(&self) -> bool { self.health > 0 }
+    fn is_visible(&self) -> bool { self.health > 0 && !self.has_effect(StatusEffectKind::Spawning) }
 
     fn get_frame(&self) -> &[f64; 4] {
         self.frame_uv()
@@ -59,31 +66,35 @@ impl Player{
         id: u32,
         spawn: [i32; 2],
         spawn_direction: LookDirection,
-        movement_controls: [Key; 4],
-        fire_control: Key,
+        rules: &GameRules,
     ) -> Player {
        
         Player{
             id,
+            team: rules.teams.get(id as usize).copied(),
             position: [spawn, spawn],
-            lives: PLAYER_LIVES,
-            health: PLAYER_SPAWN_HEALTH ,
-            armor: 0,
-            max_health: PLAYER_MAX_HEALTH,
-            max_armor: PLAYER_MAX_ARMOR ,
+            lives: rules.player_lives,
+            spawn_lives: rules.player_lives,
+            health: rules.player_spawn_health ,
+            armor: rules.player_spawn_armor,
+            max_health: rules.player_max_health,
+            max_armor: rules.player_max_armor ,
             kills: 0,
             is_alive: true ,
             spawn,
-            spawn_health: PLAYER_SPAWN_HEALTH,
-            spawn_armor: PLAYER_SPAWN_ARMOR,
+            spawn_health: rules.player_spawn_health,
+            spawn_armor: rules.player_spawn_armor,
             last_shot_dt: 0.0,
-            shot_interval: PLAYER_SHOT_INTERVAL,
-            movement_controls,
+            shot_interval: rules.shot_interval,
+            star_tier: 0,
+            effects: StatusEffects::default(),
+            respawn_delay: rules.respawn_delay,
+            respawn_countdown: 0.0,
             movement_controls_state: [false; 4],
-            fire_control,
             fire_control_state: false,
             direction: spawn_direction,
             spawn_direction,
+            sliding: None,
             tiles: TANK_1_TILES,
         }
     }
@@ -93,8 +104,13 @@ impl Player{
         self
     }
 
-    pub fn on_frame(&mut self, dt: f64) {
+    /// Returns the status effects that ran out.
+    pub fn on_frame(&mut self, dt: f64) -> Vec<StatusEffectKind> {
         self.last_shot_dt += dt;
+        if !self.is_alive {
+            self.respawn_countdown = (self.respawn_countdown - dt).max(0.0);
+        }
+        self.effects.on_frame(dt)
     }
 
     pub fn shoot(&mut self) -> bool {
@@ -103,7 +119,49 @@ impl Player{
         true
     }
 
-    pub fn is_reloading(&self) -> bool { self.last_shot_dt < self.shot_interval }
+    pub fn is_reloading(&self) -> bool {
+        let mut interval = self.shot_interval;
+        if self.star_tier >= 2 { interval *= 0.5; }
+        if self.has_effect(StatusEffectKind::RapidFire) { interval *= 0.5; }
+        self.last_shot_dt < interval
+    }
+
+    pub fn get_star_tier(&self) -> u32 { self.star_tier }
+
+    /// Tiles a bullet of this tank flies per tick.
+    pub fn get_shot_speed(&self) -> u32 { if self.star_tier >= 1 { 2 } else { 1 } }
+
+    /// Power of the bullets this tank fires, 1 for a regular shot.
+    pub fn get_shot_power(&self) -> u32 {
+        let star = if self.star_tier >= 3 { 2 } else { 1 };
+        star + self.has_effect(StatusEffectKind::DamageBoost) as u32
+    }
+
+    /// Tiles driven per tick.
+    pub fn get_move_speed(&self) -> u32 { if self.has_effect(StatusEffectKind::SpeedBoost) { 2 } else { 1 } }
+
+    pub fn get_effects(&self) -> &StatusEffects { &self.effects }
+    pub fn has_effect(&self, kind: StatusEffectKind) -> bool { self.effects.has(kind) }
+
+    /// Starts `kind` for `seconds`, or stacks it onto the running one. The game
+    /// applies protection, freezes and helmets itself; boosts are left to
+    /// whoever hands them out, e.g. a frontend or a scripted level.
+    pub fn apply_effect(&mut self, kind: StatusEffectKind, seconds: f64) { self.effects.apply(kind, seconds); }
+
+    pub fn is_invulnerable(&self) -> bool { self.has_effect(StatusEffectKind::Invulnerable) }
+
+    /// Invulnerable tanks blink; true while the sprite is in its off phase.
+    pub fn is_blinked_out(&self) -> bool {
+        let left = self.effects.remaining(StatusEffectKind::Invulnerable);
+        left > 0.0 && (left * 10.0) as i64 % 2 == 1
+    }
+
+    pub fn is_frozen(&self) -> bool { self.has_effect(StatusEffectKind::Frozen) }
+
+    /// Frozen or still spawning tanks can neither move nor shoot.
+    pub fn can_act(&self) -> bool {
+        !self.is_frozen() && !self.has_effect(StatusEffectKind::Spawning)
+    }
 
     pub fn get_direction(&self) -> &LookDirection { &self.direction }
     pub fn set_direction(&mut self, direction: LookDirection) { self.direction = direction; }
@@ -115,13 +173,39 @@ impl Player{
     pub fn get_position(&self) -> [i32; 2] { self.position[0] }
 
     pub fn get_id(&self) -> u32 { self.id }
+    pub fn get_team(&self) -> Option<u32> { self.team }
+
+    /// The same tank, or one on the same team.
+    pub fn is_ally_of(&self, other: &Player) -> bool {
+        self.id == other.id || (self.team.is_some() && self.team == other.team)
+    }
 
+    /// Takes a hit; false when it did nothing, i.e. while invulnerable.
+    /// Whether the hit destroyed the tank shows in `get_is_alive`.
     pub fn damage(&mut self) -> bool {
+        if self.is_invulnerable() { return false; }
         if self.armor > 0 { self.armor -= 1; } else { self.health -= 1; }
-        if self.health == 0 { self.is_alive = false; self.lives -= 1; }
-        !self.is_alive
+        if self.health == 0 { self.kill(); }
+        true
+    }
+
+    /// Destroyed outright whatever its health and armor, unless invulnerable.
+    pub(crate) fn destroy(&mut self) -> bool {
+        if !self.is_alive || self.is_invulnerable() { return false; }
+        self.health = 0;
+        self.kill();
+        true
+    }
+
+    fn kill(&mut self) {
+        self.is_alive = false;
+        self.lives = self.lives.saturating_sub(1);
+        self.respawn_countdown = self.respawn_delay;
     }
 
+    /// Seconds until a destroyed tank may come back, 0 once it can (or while alive).
+    pub fn get_respawn_countdown(&self) -> f64 { self.respawn_countdown }
+
     pub fn get_kills(&self) -> u32 { self.kills }
     pub fn inc_kill_count(&mut self) { self.kills += 1; }
     pub fn get_is_alive(&self) -> bool { self.is_alive }
@@ -129,31 +213,59 @@ impl Player{
     pub fn can_respawn(&self) -> bool { self.lives > 0 }
 
     pub fn respawn(&mut self) {
-        self.position[0] = self.spawn;
-        self.position[1] = self.spawn;
+        self.respawn_at(self.spawn, self.spawn_direction);
+    }
+
+    /// Respawns somewhere else than its own spawn point; the spawn point stays.
+    /// Comes back with no effects at all, spawn protection is up to the game.
+    pub fn respawn_at(&mut self, position: [i32; 2], direction: LookDirection) {
+        self.position[0] = position;
+        self.position[1] = position;
         self.health = self.spawn_health;
         self.armor = self.spawn_armor;
-        self.direction = self.spawn_direction;
+        self.direction = direction;
+        self.sliding = None;
+        self.star_tier = 0;
+        self.effects.clear();
+        self.respawn_countdown = 0.0;
         self.is_alive = true;
     }
 
+    pub fn is_sliding(&self) -> bool { self.sliding.is_some() }
+    pub(crate) fn set_sliding(&mut self, direction: Option<LookDirection>) { self.sliding = direction; }
+
+    /// Direction of the pending ice slide, clearing it.
+    pub(crate) fn take_sliding(&mut self) -> Option<LookDirection> { self.sliding.take() }
+
+    pub fn get_spawn(&self) -> ([i32; 2], LookDirection) { (self.spawn, self.spawn_direction) }
+
+    /// Moves the spawn point, takes effect on the next respawn or reset.
+    pub fn set_spawn(&mut self, spawn: [i32; 2], direction: LookDirection) {
+        self.spawn = spawn;
+        self.spawn_direction = direction;
+    }
+
     pub fn reset(&mut self) {
-        self.lives = PLAYER_LIVES;
+        self.lives = self.spawn_lives;
         self.kills = 0;
         self.respawn();
     }
 
     // -------- helpers ------------------------------------------------------- :)
 
-    pub fn movement_key(&self, idx: usize) -> Key { self.movement_controls[idx] }
-    pub fn set_move_pressed(&mut self, idx: usize, pressed: bool) {
-        
-        self.movement_controls_state[idx] = pressed;
+    pub fn set_action(&mut self, action: InputAction, pressed: bool) {
+        match action {
+            InputAction::MoveUp    => self.movement_controls_state[0] = pressed,
+            InputAction::MoveRight => self.movement_controls_state[1] = pressed,
+            InputAction::MoveDown  => self.movement_controls_state[2] = pressed,
+            InputAction::MoveLeft  => self.movement_controls_state[3] = pressed,
+            InputAction::Fire      => self.fire_control_state = pressed,
+        }
     }
 
-    pub fn fire_key(&self) -> Key { self.fire_control }
-    pub fn set_fire_pressed(&mut self, pressed: bool){
-        self.fire_control_state = pressed ;
+    pub fn release_all(&mut self) {
+        self.movement_controls_state = [false; 4];
+        self.fire_control_state = false;
     }
 
     pub fn get_pressed_direction(&self) -> Option<LookDirection>{
@@ -183,17 +295,12 @@ impl Player{
         }
     }
 
-    // pinston vemos si lo uso
-    pub fn on_press(&mut self, key: Key) {
-        if let Some(i) = self.movement_controls.iter().position(|k| key.eq(k)) {
-            self.movement_controls_state[i] = true; }
-        if key.eq(&self.fire_control) { self.fire_control_state = true; }
+    pub fn on_press(&mut self, action: InputAction) {
+        self.set_action(action, true);
     }
 
-    pub fn on_release(&mut self, key: Key) {
-        if let Some(i) = self.movement_controls.iter().position(|k| key.eq(k)) {
-            self.movement_controls_state[i] = false;}
-        if key.eq(&self.fire_control) { self.fire_control_state = false; }
+    pub fn on_release(&mut self, action: InputAction) {
+        self.set_action(action, false);
     }
 
     pub(crate) fn add_armor(&mut self) -> bool {
@@ -206,7 +313,74 @@ pub(crate) fn add_health(&mut self) -> bool {
     true
 }
 
+pub(crate) fn add_star(&mut self) -> bool {
+    self.star_tier = std::cmp::min(MAX_STAR_TIER, self.star_tier + 1);
+    true
+}
+
+pub(crate) fn add_life(&mut self) -> bool {
+    self.lives += 1;
+    true
+}
+
 pub fn get_health(&self) -> u32 { self.health }
 pub fn get_armor(&self)  -> u32 { self.armor  }
+pub fn get_max_health(&self) -> u32 { self.max_health }
+pub fn get_max_armor(&self)  -> u32 { self.max_armor  }
+
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tank() -> Player {
+        Player::new(0, [1, 1], LookDirection::Down, &GameRules::default())
+    }
+
+    #[test]
+    fn speed_boost_doubles_move_speed() {
+        let mut player = tank();
+        assert_eq!(player.get_move_speed(), 1);
+
+        player.apply_effect(StatusEffectKind::SpeedBoost, 2.0);
+        assert_eq!(player.get_move_speed(), 2);
+
+        player.on_frame(2.0);
+        assert_eq!(player.get_move_speed(), 1);
+    }
+
+    #[test]
+    fn rapid_fire_halves_reload_time() {
+        let mut player = tank();
+        let interval = GameRules::default().shot_interval;
+        player.on_frame(interval * 0.75);
+        assert!(player.is_reloading());
+
+        player.apply_effect(StatusEffectKind::RapidFire, 2.0);
+        assert!(!player.is_reloading());
+        assert!(player.shoot());
+        player.on_frame(interval * 0.25);
+        assert!(player.is_reloading());
+        player.on_frame(interval * 0.25);
+        assert!(!player.is_reloading());
+    }
+
+    #[test]
+    fn damage_boost_adds_shot_power() {
+        let mut player = tank();
+        assert_eq!(player.get_shot_power(), 1);
+
+        player.apply_effect(StatusEffectKind::DamageBoost, 2.0);
+        assert_eq!(player.get_shot_power(), 2);
+
+        // on top of the third star's concrete breaking shots
+        for _ in 0..3 {
+            player.add_star();
+        }
+        assert_eq!(player.get_shot_power(), 3);
+
+        player.on_frame(2.0);
+        assert_eq!(player.get_shot_power(), 2);
+    }
 }
@@ -7,6 +7,8 @@ pub struct Projectile {
     owner_id: u32,
     position: [[i32; 2]; 2],
     direction: LookDirection,
+    power: u32,
+    speed: u32,
 }
 
 impl GameRenderObject for Projectile {
@@ -37,9 +39,31 @@ impl Projectile {
             owner_id,
             position: [position, position],
             direction,
+            power: 1,
+            speed: 1,
         }
     }
 
+    pub fn with_speed(mut self, speed: u32) -> Self {
+        self.speed = speed.max(1);
+        self
+    }
+
+    /// Tiles flown per tick.
+    pub fn get_speed(&self) -> u32 {
+        self.speed
+    }
+
+    pub fn with_power(mut self, power: u32) -> Self {
+        self.power = power;
+        self
+    }
+
+    /// How hard it hits walls, see `TileFlags::armor`.
+    pub fn get_power(&self) -> u32 {
+        self.power
+    }
+
     pub fn get_owner_id(&self) -> u32 {
         self.owner_id
     }
@@ -0,0 +1,222 @@
+use graphics::{clear, rectangle, Context, Image, Text, Transformed};
+use opengl_graphics::{GlGraphics, GlyphCache, Texture};
+
+use battlecity::constants::{DEFAULT_CELL_SIZE, SCOREBOARD_HEIGHT};
+use battlecity::game::{Game, GameState};
+use battlecity::render::GameRenderObject;
+use battlecity::wall::{Wall, WallType};
+
+const BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const OVERLAY: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const WATER: [f32; 4] = [0.12, 0.35, 0.8, 1.0];
+const ICE: [f32; 4] = [0.78, 0.92, 1.0, 1.0];
+
+pub struct PistonTextures {
+    pub atlas: Texture,
+    pub game_over: Texture,
+}
+
+/// Top-down view: one atlas frame per grid tile, scoreboard strip on top.
+pub fn draw_game(
+    game: &Game,
+    tex: &PistonTextures,
+    glyphs: &mut GlyphCache,
+    selected_bot: usize,
+    c: Context,
+    g: &mut GlGraphics,
+) {
+    clear(BACKGROUND, g);
+
+    let board = c.trans(0.0, SCOREBOARD_HEIGHT);
+    let alpha = game.interpolation_alpha();
+
+    for row in game.walls() {
+        for wall in row {
+            match wall.variant() {
+                WallType::Water => draw_floor(wall, WATER, &board, g),
+                WallType::Ice => draw_floor(wall, ICE, &board, g),
+                // drawn over the tanks below
+                WallType::Forest => {}
+                _ => draw_object(wall, &tex.atlas, alpha, &board, g),
+            }
+        }
+    }
+    for pickup in game.pickups() {
+        draw_object(pickup, &tex.atlas, alpha, &board, g);
+    }
+    for player in game.players().iter().filter(|p| !p.is_blinked_out()) {
+        draw_object(player, &tex.atlas, alpha, &board, g);
+    }
+    for bullet in game.bullets() {
+        draw_object(bullet, &tex.atlas, alpha, &board, g);
+    }
+    for animation in game.animations() {
+        draw_object(animation, &tex.atlas, alpha, &board, g);
+    }
+    for wall in game.walls().iter().flatten() {
+        if wall.hides_tanks() {
+            draw_object(wall, &tex.atlas, alpha, &board, g);
+        }
+    }
+
+    draw_scoreboard(game, glyphs, &c, g);
+    draw_state_overlay(game, tex, glyphs, &c, g);
+    if game.state() == GameState::Menu {
+        draw_bot_settings(game, glyphs, selected_bot, &c, g);
+    }
+}
+
+fn draw_object<O: GameRenderObject>(
+    object: &O,
+    atlas: &Texture,
+    alpha: f32,
+    c: &Context,
+    g: &mut GlGraphics,
+) {
+    if !object.is_visible() {
+        return;
+    }
+
+    let [x, y] = object.get_interpolated_position(alpha);
+    Image::new()
+        .rect([
+            x as f64 * DEFAULT_CELL_SIZE,
+            y as f64 * DEFAULT_CELL_SIZE,
+            DEFAULT_CELL_SIZE,
+            DEFAULT_CELL_SIZE,
+        ])
+        .src_rect(*object.get_frame())
+        .draw(atlas, &c.draw_state, c.transform, g);
+}
+
+fn draw_floor(wall: &Wall, color: [f32; 4], c: &Context, g: &mut GlGraphics) {
+    let [x, y] = *wall.get_position();
+    let rect = [
+        x as f64 * DEFAULT_CELL_SIZE,
+        y as f64 * DEFAULT_CELL_SIZE,
+        DEFAULT_CELL_SIZE,
+        DEFAULT_CELL_SIZE,
+    ];
+    rectangle(color, rect, c.transform, g);
+}
+
+fn draw_scoreboard(game: &Game, glyphs: &mut GlyphCache, c: &Context, g: &mut GlGraphics) {
+    let column_width = 220.0;
+    let baseline = SCOREBOARD_HEIGHT - 3.0;
+
+    for (index, player) in game.players().iter().enumerate() {
+        // power-ups: star tier, then running effects by initial with seconds left
+        let mut powers = String::new();
+        if player.get_star_tier() > 0 {
+            powers += &format!("  *{}", player.get_star_tier());
+        }
+        for effect in player.get_effects().iter() {
+            powers += &format!("  {}{:.0}", &effect.kind.name()[..1], effect.remaining.ceil());
+        }
+        if !player.get_is_alive() && player.can_respawn() {
+            powers += &format!("  RESPAWN {:.0}", player.get_respawn_countdown().ceil());
+        }
+
+        let line = format!(
+            "P{}  HP {}  AR {}  LIVES {}  KILLS {}{}",
+            player.get_id() + 1,
+            player.get_health(),
+            player.get_armor(),
+            player.get_lives(),
+            player.get_kills(),
+            powers,
+        );
+        let transform = c.transform.trans(4.0 + index as f64 * column_width, baseline);
+        let _ = Text::new_color(TEXT_COLOR, 10).draw(&line, glyphs, &c.draw_state, transform, g);
+    }
+}
+
+// one line per bot under the menu title, the one the keys change marked
+fn draw_bot_settings(
+    game: &Game,
+    glyphs: &mut GlyphCache,
+    selected_bot: usize,
+    c: &Context,
+    g: &mut GlGraphics,
+) {
+    let columns = game.walls().first().map_or(0, |row| row.len());
+    let width = columns as f64 * DEFAULT_CELL_SIZE;
+    let height = game.walls().len() as f64 * DEFAULT_CELL_SIZE + SCOREBOARD_HEIGHT;
+
+    let mut lines = vec!["BOTS - TAB pick, D difficulty, B personality, H bots only".to_string()];
+    for index in 0..game.players().len() {
+        let Some(config) = game.bot_config(index) else { continue };
+        let marker = if index == selected_bot { ">" } else { " " };
+        lines.push(format!(
+            "{} P{}  {}  {}",
+            marker,
+            index + 1,
+            config.difficulty.name(),
+            config.personality.name()
+        ));
+    }
+
+    for (row, line) in lines.iter().enumerate() {
+        let transform = c.transform.trans(width * 0.5 - 150.0, height * 0.5 + 100.0 + row as f64 * 18.0);
+        let _ = Text::new_color(TEXT_COLOR, 12).draw(line, glyphs, &c.draw_state, transform, g);
+    }
+}
+
+fn draw_state_overlay(
+    game: &Game,
+    tex: &PistonTextures,
+    glyphs: &mut GlyphCache,
+    c: &Context,
+    g: &mut GlGraphics,
+) {
+    let columns = game.walls().first().map_or(0, |row| row.len());
+    let width = columns as f64 * DEFAULT_CELL_SIZE;
+    let height = game.walls().len() as f64 * DEFAULT_CELL_SIZE + SCOREBOARD_HEIGHT;
+
+    // winner as seen from player 0, the one sitting at this screen
+    let describe = |winner: Option<u32>, you: &str| match winner {
+        Some(0) => you.to_string(),
+        Some(id) => format!("PLAYER {} WINS", id + 1),
+        None => "DRAW".to_string(),
+    };
+
+    let title = match game.state() {
+        GameState::Playing => return,
+        GameState::Menu => match game.level() {
+            Some(level) => format!("BATTLE KILL - {} - press ENTER", level.name),
+            None => format!(
+                "BATTLE KILL - {} map (G) - press ENTER",
+                game.rules().map_generator.generator().name()
+            ),
+        },
+        GameState::Countdown => format!(
+            "ROUND {} - {}",
+            game.round(),
+            game.phase_remaining().unwrap_or(0.0).ceil() as i32
+        ),
+        GameState::Paused => "PAUSED - press P to resume".to_string(),
+        GameState::RoundOver => describe(game.round_winner(), "YOU WIN THE ROUND!"),
+        GameState::MatchOver => format!(
+            "{} - ENTER to play again",
+            describe(game.match_winner(), "YOU WIN!")
+        ),
+    };
+
+    if game.state() != GameState::Countdown {
+        rectangle(OVERLAY, [0.0, 0.0, width, height], c.transform, g);
+    }
+
+    let lost = game.state() == GameState::MatchOver && game.match_winner() != Some(0);
+    if lost {
+        use graphics::ImageSize;
+        let (w, h) = tex.game_over.get_size();
+        let (w, h) = (w as f64, h as f64);
+        Image::new()
+            .rect([(width - w) * 0.5, (height - h) * 0.5, w, h])
+            .draw(&tex.game_over, &c.draw_state, c.transform, g);
+    }
+
+    let transform = c.transform.trans(width * 0.5 - 150.0, height * 0.5 + 60.0);
+    let _ = Text::new_color(TEXT_COLOR, 18).draw(&title, glyphs, &c.draw_state, transform, g);
+}
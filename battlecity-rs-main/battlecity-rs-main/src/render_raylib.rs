@@ -0,0 +1,603 @@
+use raylib::prelude::*;
+use battlecity::animation::Animation;
+use battlecity::game::Game;
+use battlecity::render::GameRenderObject;
+use battlecity::constants::{SPRITE_HEIGHT, WALL_HEIGHT}; 
+use battlecity::wall::WallType;
+use raylib::prelude::Color;
+use battlecity::pickup::PickupType;
+
+pub struct SceneTextures {
+    pub atlas: Texture2D,
+    pub atlas_size: (f32, f32),
+}
+
+impl SceneTextures {
+    pub fn new(atlas: Texture2D) -> Self {
+        // read dims BEFORE moving atlas into the struct
+        let w = atlas.width() as f32;
+        let h = atlas.height() as f32;
+        Self { atlas, atlas_size: (w, h) }
+    }
+}
+
+
+pub fn draw_scene_3d(
+    d: &mut RaylibMode3D<RaylibDrawHandle>,
+    camera: &Camera3D,
+    game: &Game,
+    tex: &SceneTextures,
+    skip_player_id: Option<u32>,
+) {
+   
+    draw_walls_3d(d, camera, game, tex);
+
+  
+    draw_pickups_3d(d, camera, game, tex);
+
+    //  players
+    let viewer = game.players().first().map(|p| p.get_position());
+    for p in game.players() {
+        if let Some(skip) = skip_player_id {
+            if p.get_id() == skip { continue; }
+        }
+        // tanks in the forest stay out of sight
+        if viewer.is_some_and(|v| game.is_hidden_from(v, p.get_position())) { continue; }
+        draw_player_billboard(d, camera, p, tex, game.interpolation_alpha());
+    }
+
+    //
+    for anim in game.animations() {
+        draw_animation_billboard(d, camera, anim, tex);
+    }
+
+    // bullets
+     // bullets  
+    draw_bullets_3d(d, camera, game);
+
+    // pickups/animations
+    for anim in game.animations() { draw_animation_billboard(d, camera, anim, tex); }
+    draw_pickups_3d(d, camera, game, tex);
+
+
+}
+
+
+fn draw_animation_billboard(
+    d: &mut RaylibMode3D<RaylibDrawHandle>,
+    camera: &Camera3D,
+    anim: &Animation,
+    tex: &SceneTextures,
+) {
+    let [sx, sy, sw, sh] = *anim.get_frame();
+    let source = Rectangle { x: sx as f32, y: sy as f32, width: sw as f32, height: sh as f32 };
+
+    let pos = Vector3::new(anim.position.x, anim.position.y + anim.size * 0.5, anim.position.z);
+    let size = Vector2::new(anim.size, anim.size); 
+
+    d.draw_billboard_rec(
+        *camera,
+        &tex.atlas,
+        source,
+        pos,
+        size,                    
+        Color::WHITE,
+    );
+}
+
+fn draw_player_billboard(
+    d: &mut RaylibMode3D<RaylibDrawHandle>,
+    camera: &Camera3D,
+    player: &battlecity::player::Player,
+    tex: &SceneTextures,
+    alpha: f32,
+) {
+    if !player.is_visible() || player.is_blinked_out() { return; }
+
+    let [sx, sy, sw, sh] = *player.frame_uv();
+    let source = Rectangle { x: sx as f32, y: sy as f32, width: sw as f32, height: sh as f32 };
+
+    let wp = player.get_interpolated_world(alpha);
+    let pos = Vector3::new(wp.x, wp.y + SPRITE_HEIGHT * 0.5, wp.z);
+
+    let aspect = (source.width / source.height).abs().max(1e-6);
+    let size = Vector2::new(SPRITE_HEIGHT * aspect, SPRITE_HEIGHT);
+
+    d.draw_billboard_rec(
+        *camera,
+        &tex.atlas,
+        source,
+        pos,
+        size,                
+        Color::WHITE,
+    );
+}
+
+fn draw_walls_3d(
+    d: &mut RaylibMode3D<RaylibDrawHandle>,
+    camera: &Camera3D,
+    game: &Game,
+    tex: &SceneTextures,
+) {
+
+    let cube_size = Vector3::new(1.0, WALL_HEIGHT, 1.0);
+    use battlecity::wall::wall_center_for;
+    use raylib::prelude::Vector2;
+
+   for row in game.walls() {
+        for wall in row {
+            if !wall.is_visible() { continue; }
+
+            match wall.variant() {
+                WallType::Water | WallType::Ice => {
+                    // flat floor tiles, just above the ground
+                    let c = wall_center_for(*wall.get_position());
+                    let pos = Vector3::new(c.x, 0.02, c.z);
+                    d.draw_cube_v(pos, Vector3::new(1.0, 0.04, 1.0), tile_color(wall.variant()));
+                }
+                WallType::Forest => {
+                    let c = wall_center_for(*wall.get_position());
+                    let pos = Vector3::new(c.x, c.y, c.z);
+                    d.draw_cube_v(pos, cube_size, Color::new(20, 110, 40, 210));
+                }
+                WallType::Border => {
+                   
+                    let c = wall_center_for(*wall.get_position());
+                    let pos = Vector3::new(c.x, c.y, c.z);
+                    d.draw_cube_v(pos, cube_size, Color::BROWN);
+                    d.draw_cube_wires_v(pos, cube_size, Color::DARKBROWN);
+                }
+                _ => {
+                   
+                    let [sx, sy, sw, sh] = *wall.get_frame();
+                    let source = Rectangle { x: sx as f32, y: sy as f32, width: sw as f32, height: sh as f32 };
+
+                  
+                    let c = wall_center_for(*wall.get_position());
+                    let pos = Vector3::new(c.x, c.y, c.z);
+
+                  
+                    let size = Vector2::new(1.0, WALL_HEIGHT);
+
+                    d.draw_billboard_rec(
+                        *camera,
+                        &tex.atlas,
+                        source,
+                        pos,
+                        size,
+                        Color::WHITE,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Flat color of a tile in the 2D views (minimap, editor); transparent for empty.
+pub fn tile_color(tile: WallType) -> Color {
+    match tile {
+        WallType::Empty    => Color::new(0, 0, 0, 0),
+        WallType::Brick    => Color::ORANGE,
+        WallType::Concrete => Color::GRAY,
+        WallType::Net      => Color::GREEN,
+        WallType::Border   => Color::BROWN,
+        WallType::Water    => Color::new(30, 90, 200, 255),
+        WallType::Ice      => Color::new(200, 235, 255, 255),
+        WallType::Forest   => Color::DARKGREEN,
+    }
+}
+
+pub fn draw_minimap_2d(
+    d2: &mut RaylibDrawHandle,
+    game: &Game,
+    origin: Vector2,
+    tile_px: i32,
+) {
+    let walls = game.walls();
+    if walls.is_empty() { return; }
+
+    let rows = walls.len() as i32;
+    let cols = walls[0].len() as i32;
+
+    let map_w = cols * tile_px;
+    let map_h = rows * tile_px;
+
+    // background panel
+    d2.draw_rectangle(
+        origin.x as i32 - 4,
+        origin.y as i32 - 4,
+        map_w + 8,
+        map_h + 8,
+        Color::new(0, 0, 0, 180),
+    );
+
+    // tiles
+    for y in 0..rows {
+        for x in 0..cols {
+            let wall = &walls[y as usize][x as usize];
+            let color = tile_color(wall.variant());
+            if color.a > 0 {
+                d2.draw_rectangle(
+                    origin.x as i32 + x * tile_px,
+                    origin.y as i32 + y * tile_px,
+                    tile_px,
+                    tile_px,
+                    color,
+                );
+            }
+        }
+    }
+
+    
+    let viewer = game.players().first().map(|p| p.get_position());
+    for (idx, p) in game.players().iter().enumerate() {
+        if idx != 0 && viewer.is_some_and(|v| game.is_hidden_from(v, p.get_position())) {
+            continue;
+        }
+        let [px, py] = p.get_position();
+        let cx = origin.x as i32 + px * tile_px + tile_px / 2;
+        let cy = origin.y as i32 + py * tile_px + tile_px / 2;
+
+        let col = if idx == 0 { Color::YELLOW } else { Color::BLUE };
+        d2.draw_circle(cx, cy, (tile_px as f32 * 0.35).max(2.0), col);
+    }
+
+
+    for p in game.pickups() {
+    let [px, py] = p.get_position();
+    let cx = origin.x as i32 + px * tile_px + tile_px / 2;
+    let cy = origin.y as i32 + py * tile_px + tile_px / 2;
+
+    let dot_color = match p.get_variant() {
+        PickupType::Health => Color::PINK,
+        PickupType::Armor  => Color::SKYBLUE,
+        _                  => Color::GOLD,
+    };
+
+    
+    d2.draw_rectangle(cx - 2, cy - 2, 4, 4, dot_color);
+  
+}
+}
+
+fn draw_pickups_3d(
+    d: &mut RaylibMode3D<RaylibDrawHandle>,
+    camera: &Camera3D,
+    game: &Game,
+    tex: &SceneTextures,
+) {
+    let t = 0.0_f32; 
+    for p in game.pickups() {
+        let [sx, sy, sw, sh] = *p.get_frame();
+        let source = Rectangle { x: sx as f32, y: sy as f32, width: sw as f32, height: sh as f32 };
+
+        let tile = *p.get_position();
+        let wp = battlecity::transform::tile_to_world(tile);
+        let y = wp.y + 0.45 + (t * 3.0).sin() * 0.05;   
+        let pos = Vector3::new(wp.x, y, wp.z);
+
+        let size = Vector2::new(0.6, 0.6);             
+        d.draw_billboard_rec(
+            *camera,
+            &tex.atlas,
+            source,
+            pos,
+            size,
+            Color::WHITE,
+        );
+    }
+}
+
+pub fn draw_hud_bar(
+    d2: &mut RaylibDrawHandle,
+    game: &battlecity::game::Game,
+) {
+    let Some(p0) = game.players().get(0) else { return; };
+
+    let screen_w = d2.get_screen_width();
+    let screen_h = d2.get_screen_height();
+
+    // --- layout ---
+    let pad     = 10;
+    let height  = 64;               // HUD bar height
+    let y       = screen_h - height - pad;
+    let x       = pad;
+    let width   = screen_w - pad*2;
+
+    // panel background
+    d2.draw_rectangle_rounded(
+        Rectangle { x: x as f32, y: y as f32, width: width as f32, height: height as f32 },
+        0.12,
+        10,
+        Color::new(0, 0, 0, 180),
+    );
+
+    // inner padding
+    let inner_pad = 12;
+    let mut cursor_x = x + inner_pad;
+    let center_y = y + height/2;
+
+    // --- HEALTH ---
+    {
+        let label = "HEALTH";
+        d2.draw_text(label, cursor_x, center_y - 24, 18, Color::LIME);
+
+        let bar_x = cursor_x;
+        let bar_y = center_y - 10;
+        let bar_w = 220;
+        let bar_h = 18;
+
+        // bar background
+        d2.draw_rectangle(bar_x, bar_y, bar_w, bar_h, Color::DARKGRAY);
+        // fill
+        let h_cur = p0.get_health().min(p0.get_max_health()) as f32;
+        let h_max = p0.get_max_health().max(1) as f32;
+        let fill_w = ((h_cur / h_max) * bar_w as f32).round() as i32;
+        d2.draw_rectangle(bar_x, bar_y, fill_w, bar_h, Color::GREEN);
+
+        // numbers
+        let txt = format!("{}/{}", p0.get_health(), p0.get_max_health());
+        d2.draw_text(&txt, bar_x + bar_w + 10, bar_y - 2, 20, Color::WHITE);
+
+        cursor_x += bar_w + 170; // advance layout
+    }
+
+    // --- ARMOR ---
+    {
+        let label = "ARMOR";
+        d2.draw_text(label, cursor_x, center_y - 24, 18, Color::SKYBLUE);
+
+        let bar_x = cursor_x;
+        let bar_y = center_y - 10;
+        let bar_w = 220;
+        let bar_h = 18;
+
+        d2.draw_rectangle(bar_x, bar_y, bar_w, bar_h, Color::DARKGRAY);
+        let a_cur = p0.get_armor().min(p0.get_max_armor()) as f32;
+        let a_max = p0.get_max_armor().max(1) as f32;
+        let fill_w = ((a_cur / a_max) * bar_w as f32).round() as i32;
+        d2.draw_rectangle(bar_x, bar_y, fill_w, bar_h, Color::BLUE);
+
+        let txt = format!("{}/{}", p0.get_armor(), p0.get_max_armor());
+        d2.draw_text(&txt, bar_x + bar_w + 10, bar_y - 2, 20, Color::WHITE);
+
+        cursor_x += bar_w + 170;
+    }
+
+    // --- POWER-UPS ---
+    {
+        d2.draw_text("POWER", cursor_x, center_y - 24, 18, Color::GOLD);
+
+        let mut status = vec![];
+        if p0.get_star_tier() > 0 {
+            status.push(format!("STAR {}", p0.get_star_tier()));
+        }
+        for effect in p0.get_effects().iter() {
+            status.push(format!("{} {:.0}s", effect.kind.name().to_uppercase(), effect.remaining.ceil()));
+        }
+        let txt = if status.is_empty() { "-".to_string() } else { status.join("  ") };
+        d2.draw_text(&txt, cursor_x, center_y - 2, 20, Color::WHITE);
+    }
+
+    // --- RESPAWN (centered above the bar while dead) ---
+    if !p0.get_is_alive() {
+        let txt = if !p0.can_respawn() {
+            "OUT OF LIVES".to_string()
+        } else if p0.get_respawn_countdown() > 0.0 {
+            format!("RESPAWN IN {:.0}", p0.get_respawn_countdown().ceil())
+        } else {
+            "PRESS FIRE TO RESPAWN".to_string()
+        };
+        let tw = d2.measure_text(&txt, 30);
+        d2.draw_text(&txt, (screen_w - tw) / 2, y - 50, 30, Color::ORANGE);
+    }
+
+    // --- LIVES & KILLS (right-aligned block) ---
+    {
+        let right_pad = 16;
+        let block_w = 200;
+        let rx = screen_w - block_w - right_pad;
+
+        // lives
+        let lives_txt = format!("Lives: {}", p0.get_lives());
+        d2.draw_text(&lives_txt, rx, center_y - 24, 20, Color::YELLOW);
+
+        // kills
+        let kills_txt = format!("Kills: {}", p0.get_kills());
+        d2.draw_text(&kills_txt, rx, center_y + 2, 20, Color::ORANGE);
+    }
+}
+
+
+// how long a kill stays on screen, seconds
+const KILL_FEED_TTL: f32 = 4.0;
+const KILL_FEED_MAX: usize = 5;
+
+/// Latest kills and power-ups, newest last, fed from `GameEvent::TankDestroyed`
+/// and `GameEvent::PickupCollected`.
+#[derive(Default)]
+pub struct KillFeed {
+    entries: Vec<(String, f32)>,
+}
+
+impl KillFeed {
+    pub fn push_kill(&mut self, killer: u32, victim: u32) {
+        let line = if killer == victim {
+            format!("P{} blew themselves up", victim + 1)
+        } else {
+            format!("P{} destroyed P{}", killer + 1, victim + 1)
+        };
+        self.entries.push((line, KILL_FEED_TTL));
+        if self.entries.len() > KILL_FEED_MAX {
+            self.entries.remove(0);
+        }
+    }
+
+    pub fn push_pickup(&mut self, player: u32, pickup: PickupType) {
+        self.entries.push((format!("P{} got {}", player + 1, pickup.name()), KILL_FEED_TTL));
+        if self.entries.len() > KILL_FEED_MAX {
+            self.entries.remove(0);
+        }
+    }
+
+    pub fn on_frame(&mut self, dt: f32) {
+        self.entries.retain_mut(|(_, ttl)| {
+            *ttl -= dt;
+            *ttl > 0.0
+        });
+    }
+}
+
+pub fn draw_kill_feed(d2: &mut RaylibDrawHandle, feed: &KillFeed) {
+    let screen_w = d2.get_screen_width();
+    let size = 20;
+
+    for (i, (line, ttl)) in feed.entries.iter().enumerate() {
+        let w = d2.measure_text(line, size);
+        let fade = (ttl / 1.0).min(1.0);
+        let color = Color::new(255, 255, 255, (255.0 * fade) as u8);
+        d2.draw_text(line, screen_w - w - 16, 16 + i as i32 * (size + 6), size, color);
+    }
+}
+
+fn draw_bullets_3d(
+    d: &mut RaylibMode3D<RaylibDrawHandle>,
+    camera: &Camera3D,
+    game: &battlecity::game::Game,
+) {
+    let alpha = game.interpolation_alpha();
+    for b in game.bullets() {
+        let wp = b.get_interpolated_world(alpha);
+        // small glowing billboard
+        let pos = Vector3::new(wp.x, wp.y + 0.45, wp.z);
+        let size = Vector2::new(0.2, 0.2);
+        // simple colored quad (no texture): use a tiny cube for visibility
+        d.draw_cube_v(Vector3::new(pos.x, pos.y, pos.z), Vector3::new(0.15, 0.15, 0.15), Color::YELLOW);
+    }
+}
+
+use battlecity::game::GameState;
+
+pub fn draw_state_banner_2d(d: &mut RaylibDrawHandle, game: &battlecity::game::Game) {
+    let state = game.state();
+    if state == GameState::Playing || state == GameState::Menu { return; }
+
+    let sw = d.get_screen_width() as f32;
+    let sh = d.get_screen_height() as f32;
+
+    // winner as seen from player 0, the one sitting at this screen
+    let describe = |winner: Option<u32>, you: &str| match winner {
+        Some(0) => you.to_string(),
+        Some(id) => format!("PLAYER {} WINS", id + 1),
+        None => "DRAW".to_string(),
+    };
+
+    let (title, hint, color) = match state {
+        GameState::Countdown => (
+            format!("ROUND {}", game.round()),
+            format!("{}", game.phase_remaining().unwrap_or(0.0).ceil() as i32),
+            Color::YELLOW,
+        ),
+        GameState::Paused => ("PAUSED".to_string(), "Press P to resume".to_string(), Color::RAYWHITE),
+        GameState::RoundOver => (
+            describe(game.round_winner(), "YOU WIN THE ROUND!"),
+            format!("Round {} over", game.round()),
+            if game.round_winner() == Some(0) { Color::YELLOW } else { Color::RED },
+        ),
+        GameState::MatchOver => (
+            describe(game.match_winner(), "YOU WIN!"),
+            "Press ENTER to play again or R for the menu".to_string(),
+            if game.match_winner() == Some(0) { Color::YELLOW } else { Color::RED },
+        ),
+        GameState::Playing
+        | GameState::Menu => return, // nothing to draw in these states
+    };
+
+    // Fade-in over 1s, the countdown stays see-through so the map is visible
+    let max_alpha = if state == GameState::Countdown { 60.0 } else { 160.0 };
+    let alpha = game.state_time().min(1.0);
+    let bg = Color::new(0, 0, 0, (max_alpha * alpha) as u8);
+
+    d.draw_rectangle(0, 0, sw as i32, sh as i32, bg);
+
+    let title_size = 40;
+    let hint_size  = 20;
+
+    let tw = d.measure_text(&title, title_size) as f32;
+    let hw = d.measure_text(&hint, hint_size) as f32;
+
+    let cx = sw * 0.5;
+    let cy = sh * 0.45;
+
+    d.draw_text(&title, (cx - tw * 0.5) as i32, (cy - 20.0) as i32, title_size, color);
+    d.draw_text(
+        &hint,
+        (cx - hw * 0.5) as i32,
+        (cy + 30.0) as i32,
+        hint_size,
+        Color::RAYWHITE
+    );
+}
+
+pub fn draw_menu(d: &mut RaylibDrawHandle, game: &Game, selected_bot: usize) {
+    use raylib::prelude::Color;
+
+    let sw = d.get_screen_width();
+    let sh = d.get_screen_height();
+
+    d.clear_background(Color::BLACK);
+
+    let title = " BATTLE KILL";
+    let rules = [
+        "Rules:",
+        "1. Be the last tank standing to win the round.",
+        "2. Collect hearts for health.",
+        "3. Collect shields for armor.",
+        "4. Avoid enemy bullets!",
+    ];
+    let hint = "Press ENTER to Start - P pauses - F2 level editor";
+
+    let title_size = 40;
+    let text_size = 20;
+
+    let tw = d.measure_text(title, title_size);
+    d.draw_text(title, (sw/2 - tw/2), (sh/4), title_size, Color::YELLOW);
+
+    let mut y = sh/3;
+    for line in rules.iter() {
+        let lw = d.measure_text(line, text_size);
+        d.draw_text(line, (sw/2 - lw/2), y, text_size, Color::RAYWHITE);
+        y += 30;
+    }
+
+    let hw = d.measure_text(hint, text_size);
+    d.draw_text(hint, (sw/2 - hw/2), y + 40, text_size, Color::GREEN);
+
+    let map = match game.level() {
+        Some(level) => format!("Map: {}", level.name),
+        None => format!("Map: {} - G to change", game.rules().map_generator.generator().name()),
+    };
+    let mw = d.measure_text(&map, text_size);
+    d.draw_text(&map, (sw/2 - mw/2), y + 80, text_size, Color::SKYBLUE);
+
+    // one line per bot, the one the keys change is highlighted
+    let mut line_y = y + 120;
+    let bots_hint = "Bots - TAB to pick, D difficulty, B personality, H bots only";
+    let bw = d.measure_text(bots_hint, text_size);
+    d.draw_text(bots_hint, (sw/2 - bw/2), line_y, text_size, Color::GRAY);
+    for index in 0..game.players().len() {
+        let Some(config) = game.bot_config(index) else { continue };
+        line_y += 26;
+        let marker = if index == selected_bot { ">" } else { " " };
+        let line = format!(
+            "{} P{}  {}  {}",
+            marker,
+            index + 1,
+            config.difficulty.name(),
+            config.personality.name()
+        );
+        let color = if index == selected_bot { Color::YELLOW } else { Color::RAYWHITE };
+        let lw = d.measure_text(&line, text_size);
+        d.draw_text(&line, (sw/2 - lw/2), line_y, text_size, color);
+    }
+}
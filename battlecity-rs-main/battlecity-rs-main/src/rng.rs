@@ -0,0 +1,27 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// The single random source owned by a `Game`. ChaCha8 gives the same stream
+/// for the same seed on every platform and rand release, so a seed is enough
+/// to reproduce a map or a whole match.
+pub type GameRng = ChaCha8Rng;
+
+pub fn rng_from_seed(seed: u64) -> GameRng {
+    GameRng::seed_from_u64(seed)
+}
+
+/// Fresh seed for matches that were not given one.
+pub fn random_seed() -> u64 {
+    rand::random()
+}
+
+/// Picks `--seed <n>` out of a command line so shared maps can be replayed.
+pub fn seed_from_args<I: IntoIterator<Item = String>>(args: I) -> Option<u64> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args.next().and_then(|value| value.parse().ok());
+        }
+    }
+    None
+}
@@ -0,0 +1,300 @@
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    ARMOR_SPAWN_TIME, COLUMN_COUNT, COUNTDOWN_DURATION, GAME_TICK_INTERVAL, HEALTH_SPAWN_TIME,
+    MAX_SPAWNED_PICKUPS, PLAYER_LIVES, PLAYER_MAX_ARMOR, PLAYER_MAX_HEALTH, PLAYER_SHOT_INTERVAL,
+    PLAYER_SPAWN_ARMOR, PLAYER_SPAWN_HEALTH, ROUNDS_TO_WIN, ROUND_OVER_DURATION, ROW_COUNT,
+    CONCRETE_RATIO, NET_RATIO, WALL_DENSITY, WALL_REGEN_TIME, STAR_SPAWN_TIME, SHOVEL_SPAWN_TIME,
+    GRENADE_SPAWN_TIME, CLOCK_SPAWN_TIME, HELMET_SPAWN_TIME, TANK_SPAWN_TIME, SHOVEL_DURATION,
+    CLOCK_DURATION, HELMET_DURATION, SPAWN_PROTECTION_TIME, RESPAWN_DELAY,
+};
+use crate::bot::BotConfig;
+use crate::level::MAX_SPAWNS;
+use crate::mapgen::MapGeneratorKind;
+
+/// Balance and match settings handed to `Game::new`. Defaults are the values in
+/// `constants.rs`; a rules file only needs the keys it changes, e.g.
+///
+/// ```toml
+/// player_lives = 5
+/// shot_interval = 0.3
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameRules {
+    pub column_count: u8,
+    pub row_count: u8,
+    /// Seconds per simulation tick; tanks and bullets move one tile per tick.
+    pub tick_interval: f64,
+
+    pub player_lives: u32,
+    pub player_max_health: u32,
+    pub player_spawn_health: u32,
+    pub player_max_armor: u32,
+    pub player_spawn_armor: u32,
+    /// Seconds between two shots of the same tank.
+    pub shot_interval: f64,
+    /// Seconds of invulnerability after every (re)spawn.
+    pub spawn_protection_time: f64,
+    /// Seconds a destroyed tank waits before it comes back.
+    pub respawn_delay: f64,
+    /// After the delay, wait for the tank's fire button too instead of
+    /// respawning right away.
+    pub respawn_requires_fire: bool,
+
+    pub max_pickups: usize,
+    /// Seconds between two spawns of each kind of pickup.
+    pub armor_spawn_time: f64,
+    pub health_spawn_time: f64,
+    pub star_spawn_time: f64,
+    pub shovel_spawn_time: f64,
+    pub grenade_spawn_time: f64,
+    pub clock_spawn_time: f64,
+    pub helmet_spawn_time: f64,
+    pub tank_spawn_time: f64,
+    /// Seconds the walls around a spawn stay concrete after a shovel.
+    pub shovel_duration: f64,
+    /// Seconds the other tanks stay frozen after a clock.
+    pub clock_duration: f64,
+    /// Seconds of invulnerability from a helmet.
+    pub helmet_duration: f64,
+
+    pub countdown_duration: f64,
+    pub round_over_duration: f64,
+    pub rounds_to_win: u32,
+
+    /// How random maps are built: noise, symmetric, caves, rooms or maze.
+    pub map_generator: MapGeneratorKind,
+    /// Share of the map covered by walls, 0..1.
+    pub wall_density: f64,
+    /// Share of the walls that are concrete, and that are nets; the rest is brick.
+    pub concrete_ratio: f64,
+    pub net_ratio: f64,
+    /// Seconds after its last hit before a damaged or destroyed wall is whole
+    /// again; 0 keeps shot walls down for the rest of the round.
+    pub wall_regen_time: f64,
+
+    /// How many tanks, from the first slot on, are played by people; 0 leaves
+    /// every tank to the bots.
+    pub humans: usize,
+    /// Team of each tank in slot order. Tanks on the same team cannot hurt each
+    /// other and win rounds together; tanks without an entry play alone.
+    pub teams: Vec<u32>,
+    /// Difficulty and personality of each bot, in slot order after the human
+    /// ones; bots without an entry get `BotConfig::default()`.
+    pub bots: Vec<BotConfig>,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules {
+            column_count: COLUMN_COUNT,
+            row_count: ROW_COUNT,
+            tick_interval: GAME_TICK_INTERVAL,
+            player_lives: PLAYER_LIVES,
+            player_max_health: PLAYER_MAX_HEALTH,
+            player_spawn_health: PLAYER_SPAWN_HEALTH,
+            player_max_armor: PLAYER_MAX_ARMOR,
+            player_spawn_armor: PLAYER_SPAWN_ARMOR,
+            shot_interval: PLAYER_SHOT_INTERVAL,
+            spawn_protection_time: SPAWN_PROTECTION_TIME,
+            respawn_delay: RESPAWN_DELAY,
+            respawn_requires_fire: false,
+            max_pickups: MAX_SPAWNED_PICKUPS,
+            armor_spawn_time: ARMOR_SPAWN_TIME,
+            health_spawn_time: HEALTH_SPAWN_TIME,
+            star_spawn_time: STAR_SPAWN_TIME,
+            shovel_spawn_time: SHOVEL_SPAWN_TIME,
+            grenade_spawn_time: GRENADE_SPAWN_TIME,
+            clock_spawn_time: CLOCK_SPAWN_TIME,
+            helmet_spawn_time: HELMET_SPAWN_TIME,
+            tank_spawn_time: TANK_SPAWN_TIME,
+            shovel_duration: SHOVEL_DURATION,
+            clock_duration: CLOCK_DURATION,
+            helmet_duration: HELMET_DURATION,
+            countdown_duration: COUNTDOWN_DURATION,
+            round_over_duration: ROUND_OVER_DURATION,
+            rounds_to_win: ROUNDS_TO_WIN,
+            map_generator: MapGeneratorKind::default(),
+            wall_density: WALL_DENSITY,
+            concrete_ratio: CONCRETE_RATIO,
+            net_ratio: NET_RATIO,
+            wall_regen_time: WALL_REGEN_TIME,
+            humans: 1,
+            teams: vec![],
+            bots: vec![],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RulesError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::Io(err) => write!(f, "cannot read rules file: {}", err),
+            RulesError::Parse(err) => write!(f, "malformed rules file: {}", err),
+            RulesError::Invalid(reason) => write!(f, "invalid rules: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+impl GameRules {
+    pub fn from_toml_str(source: &str) -> Result<GameRules, RulesError> {
+        let rules: GameRules = toml::from_str(source).map_err(RulesError::Parse)?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<GameRules, RulesError> {
+        let source = fs::read_to_string(path).map_err(RulesError::Io)?;
+        GameRules::from_toml_str(&source)
+    }
+
+    /// Loads `--rules <file>` from a command line, defaults when it is absent.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<GameRules, RulesError> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--rules" {
+                return match args.next() {
+                    Some(path) => GameRules::load(path),
+                    None => Err(RulesError::Invalid("--rules needs a file path".to_string())),
+                };
+            }
+        }
+        Ok(GameRules::default())
+    }
+
+    /// Settings of the `n`th bot (0 = the first slot after the humans).
+    pub fn bot(&self, n: usize) -> BotConfig {
+        self.bots.get(n).copied().unwrap_or_default()
+    }
+
+    /// Changes the `n`th bot's settings, filling the slots before it with defaults.
+    pub fn set_bot(&mut self, n: usize, config: BotConfig) {
+        if self.bots.len() <= n {
+            self.bots.resize(n + 1, BotConfig::default());
+        }
+        self.bots[n] = config;
+    }
+
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string_pretty(self).expect("rules always serialize")
+    }
+
+    /// Rejects values the simulation cannot run with.
+    pub fn validate(&self) -> Result<(), RulesError> {
+        let invalid = |reason: &str| Err(RulesError::Invalid(reason.to_string()));
+
+        if self.column_count < 5 || self.row_count < 5 {
+            return invalid("the map must be at least 5x5 tiles");
+        }
+        if self.tick_interval <= 0.0 {
+            return invalid("tick_interval must be positive");
+        }
+        if self.player_spawn_health == 0 || self.player_spawn_health > self.player_max_health {
+            return invalid("player_spawn_health must be between 1 and player_max_health");
+        }
+        if self.player_spawn_armor > self.player_max_armor {
+            return invalid("player_spawn_armor cannot exceed player_max_armor");
+        }
+        if self.rounds_to_win == 0 {
+            return invalid("rounds_to_win must be at least 1");
+        }
+        let is_ratio = |value: f64| (0.0..=1.0).contains(&value);
+        if !is_ratio(self.wall_density) || !is_ratio(self.concrete_ratio) || !is_ratio(self.net_ratio) {
+            return invalid("wall_density, concrete_ratio and net_ratio must be between 0 and 1");
+        }
+        if self.concrete_ratio + self.net_ratio > 1.0 {
+            return invalid("concrete_ratio and net_ratio cannot add up to more than 1");
+        }
+        if self.wall_regen_time < 0.0 {
+            return invalid("wall_regen_time cannot be negative");
+        }
+        if self.shovel_duration < 0.0 || self.clock_duration < 0.0 || self.helmet_duration < 0.0 {
+            return invalid("power-up durations cannot be negative");
+        }
+        if self.spawn_protection_time < 0.0 || self.respawn_delay < 0.0 {
+            return invalid("spawn_protection_time and respawn_delay cannot be negative");
+        }
+        if self.humans > MAX_SPAWNS {
+            return Err(RulesError::Invalid(format!("humans cannot exceed the {} tanks of a match", MAX_SPAWNS)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_invalid(source: &str) -> bool {
+        matches!(GameRules::from_toml_str(source), Err(RulesError::Invalid(_)))
+    }
+
+    #[test]
+    fn shipped_rules_file_is_the_defaults() {
+        let shipped = GameRules::from_toml_str(include_str!("../resources/rules.toml")).unwrap();
+        assert_eq!(shipped, GameRules::default());
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut rules = GameRules { player_lives: 7, teams: vec![0, 1, 0, 1], ..GameRules::default() };
+        rules.set_bot(1, BotConfig::default().next_difficulty());
+        assert_eq!(GameRules::from_toml_str(&rules.to_toml_string()).unwrap(), rules);
+    }
+
+    #[test]
+    fn missing_keys_keep_their_default() {
+        let rules = GameRules::from_toml_str("player_lives = 5\nshot_interval = 0.3\n").unwrap();
+        assert_eq!(rules.player_lives, 5);
+        assert_eq!(rules.shot_interval, 0.3);
+        assert_eq!(rules.column_count, GameRules::default().column_count);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(matches!(GameRules::from_toml_str("player_livez = 5"), Err(RulesError::Parse(_))));
+    }
+
+    #[test]
+    fn rejects_what_the_simulation_cannot_run() {
+        assert!(is_invalid("column_count = 4"));
+        assert!(is_invalid("tick_interval = 0.0"));
+        assert!(is_invalid("player_spawn_health = 4"));
+        assert!(is_invalid("player_spawn_armor = 4"));
+        assert!(is_invalid("rounds_to_win = 0"));
+        assert!(is_invalid("wall_density = 1.5"));
+        assert!(is_invalid("concrete_ratio = 0.6\nnet_ratio = 0.6"));
+        assert!(is_invalid("respawn_delay = -1.0"));
+        assert!(is_invalid("humans = 5"));
+        assert!(!is_invalid("humans = 4"));
+    }
+
+    #[test]
+    fn from_args_loads_the_rules_flag() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(GameRules::from_args(args(&["--seed", "3"])).unwrap(), GameRules::default());
+        assert!(matches!(GameRules::from_args(args(&["--rules"])), Err(RulesError::Invalid(_))));
+        assert!(matches!(
+            GameRules::from_args(args(&["--rules", "does/not/exist.toml"])),
+            Err(RulesError::Io(_))
+        ));
+        let path = std::env::temp_dir().join("battlecity-rules-test.toml");
+        fs::write(&path, "player_lives = 9\n").unwrap();
+        let rules = GameRules::from_args(args(&["--rules", path.to_str().unwrap()])).unwrap();
+        assert_eq!(rules.player_lives, 9);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -19,6 +19,26 @@ impl LookDirection {
             LookDirection::Right => [x + 1, y],
         }
     }
+
+    pub fn opposite(&self) -> LookDirection {
+        match self {
+            LookDirection::Up => LookDirection::Down,
+            LookDirection::Down => LookDirection::Up,
+            LookDirection::Left => LookDirection::Right,
+            LookDirection::Right => LookDirection::Left,
+        }
+    }
+
+    /// Direction of the step from `from` to the neighbouring tile `to`.
+    pub fn towards(from: [i32; 2], to: [i32; 2]) -> Option<LookDirection> {
+        match [to[0] - from[0], to[1] - from[1]] {
+            [0, -1] => Some(LookDirection::Up),
+            [0, 1] => Some(LookDirection::Down),
+            [-1, 0] => Some(LookDirection::Left),
+            [1, 0] => Some(LookDirection::Right),
+            _ => None,
+        }
+    }
 }
 
 // transform.rs
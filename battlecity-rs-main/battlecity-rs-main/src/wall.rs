@@ -1,4 +1,10 @@
-use crate::{render::GameRenderObject, constants::{BRICK_TILE, CONCRETE_TILE, EMPTY_FRAME_TILE, NET_TILE, WALL_HEIGHT}};
+use crate::{
+    render::GameRenderObject,
+    constants::{
+        BRICK_DAMAGED_1_TILE, BRICK_DAMAGED_2_TILE, BRICK_TILE, CONCRETE_DAMAGED_TILE,
+        CONCRETE_TILE, EMPTY_FRAME_TILE, NET_TILE, WALL_HEIGHT,
+    },
+};
 use glam::Vec3;
 use crate::transform::tile_to_world;
 
@@ -15,11 +21,87 @@ pub enum WallType {
     Net,
     Empty,
     Border, 
+    /// Tanks cannot cross it, bullets fly over.
+    Water,
+    /// Open ground, but a tank driving onto it slides one more tile.
+    Ice,
+    /// Passable by tanks and bullets; hides whoever is inside.
+    Forest,
+}
+
+/// How a tile type interacts with tanks, bullets and bots. Each check in the
+/// game asks for the one flag it cares about, so a new terrain type only needs
+/// a row in `WallType::flags`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TileFlags {
+    /// Tanks cannot drive onto the tile.
+    pub blocks_tanks: bool,
+    /// Bullets stop here (and damage the tile if it is destructible).
+    pub blocks_bullets: bool,
+    /// Bots cannot see through the tile.
+    pub blocks_sight: bool,
+    /// A tank standing on the tile cannot be seen from a distance.
+    pub hides_tanks: bool,
+    /// A tank driving onto the tile slides one more tile.
+    pub slippery: bool,
+    /// Damage it takes before turning into `Empty`, 0 for indestructible.
+    pub hit_points: u32,
+    /// Shot power this soaks up per hit: a shot needs more power than this to
+    /// chip the tile at all, every point above it is one hit point of damage.
+    pub armor: u32,
+}
+
+impl TileFlags {
+    const OPEN: TileFlags = TileFlags {
+        blocks_tanks: false,
+        blocks_bullets: false,
+        blocks_sight: false,
+        hides_tanks: false,
+        slippery: false,
+        hit_points: 0,
+        armor: 0,
+    };
+
+    const SOLID: TileFlags = TileFlags {
+        blocks_tanks: true,
+        blocks_bullets: true,
+        blocks_sight: true,
+        ..TileFlags::OPEN
+    };
+
+    /// Hit points a shot of `power` takes off, 0 when it bounces off.
+    pub fn damage_from(&self, power: u32) -> u32 {
+        if self.hit_points == 0 { 0 } else { power.saturating_sub(self.armor) }
+    }
+
+    /// Regular shots can break it.
+    pub fn is_destructible(&self) -> bool {
+        self.damage_from(1) > 0
+    }
+}
+
+impl WallType {
+    pub fn flags(self) -> TileFlags {
+        match self {
+            WallType::Empty | WallType::Net => TileFlags::OPEN,
+            WallType::Brick => TileFlags { hit_points: 3, ..TileFlags::SOLID },
+            // only upgraded shots get through
+            WallType::Concrete => TileFlags { hit_points: 2, armor: 1, ..TileFlags::SOLID },
+            WallType::Border => TileFlags::SOLID,
+            WallType::Water => TileFlags { blocks_tanks: true, ..TileFlags::OPEN },
+            WallType::Ice => TileFlags { slippery: true, ..TileFlags::OPEN },
+            WallType::Forest => TileFlags { blocks_sight: true, hides_tanks: true, ..TileFlags::OPEN },
+        }
+    }
 }
 
 pub struct Wall {
     variant: WallType,
+    // what was placed here, a destroyed wall grows back into it
+    built: WallType,
     position: [i32; 2],
+    hit_points: u32,
+    since_hit: f64,
 }
 
 impl GameRenderObject for Wall {
@@ -44,7 +126,10 @@ impl Wall {
     pub fn new(position: [i32; 2]) -> Wall {
         Wall {
             variant: WallType::Empty,
+            built: WallType::Empty,
             position,
+            hit_points: 0,
+            since_hit: 0.0,
         }
     }
 
@@ -52,110 +137,113 @@ impl Wall {
         self.variant
     }
 
-    pub fn brick(mut self) -> Self {
-        self.variant = WallType::Brick;
-        self
+    pub fn brick(self) -> Self {
+        self.with_variant(WallType::Brick)
     }
 
-    pub fn concrete(mut self) -> Self {
-        self.variant = WallType::Concrete;
-        self
+    pub fn concrete(self) -> Self {
+        self.with_variant(WallType::Concrete)
     }
 
-    pub fn net(mut self) -> Self {
-        self.variant = WallType::Net;
-        self
+    pub fn net(self) -> Self {
+        self.with_variant(WallType::Net)
     }
 
-    pub fn empty(mut self) -> Self {
-        self.variant = WallType::Empty;
-        self
+    pub fn empty(self) -> Self {
+        self.with_variant(WallType::Empty)
     }
 
-    pub fn damage(&mut self) {
-        match self.variant {
-            WallType::Brick => self.variant = WallType::Empty,
-            WallType::Concrete => self.variant = WallType::Concrete,
-            WallType::Net => self.variant = WallType::Net,
-            WallType::Empty => self.variant = WallType::Empty,
-            WallType::Border   => self.variant = WallType::Border,
-        }
+    pub fn flags(&self) -> TileFlags {
+        self.variant.flags()
     }
 
-    pub fn is_solid(&self) -> bool {
-        match self.variant {
-            WallType::Brick | WallType::Concrete => true,
-            WallType::Net | WallType::Empty => false,
-            WallType::Brick | WallType::Concrete | WallType::Border => true,    
+    /// Hits left before a destructible tile breaks, 0 for indestructible ones.
+    pub fn get_hit_points(&self) -> u32 {
+        self.hit_points
+    }
+
+    /// Takes a shot of `power`; a tile out of hit points turns into `Empty`.
+    /// Returns whether the shot did any damage.
+    pub fn damage(&mut self, power: u32) -> bool {
+        let damage = self.flags().damage_from(power);
+        if damage == 0 { return false; }
+
+        self.hit_points = self.hit_points.saturating_sub(damage);
+        if self.hit_points == 0 {
+            self.variant = WallType::Empty;
         }
+        self.since_hit = 0.0;
+        true
     }
 
-    pub fn get_frame(&self) -> &[f64; 4] {
-        match self.variant {
-            WallType::Brick => &BRICK_TILE,
-            WallType::Concrete => &CONCRETE_TILE,
-            WallType::Net => &NET_TILE,
-            WallType::Empty => &EMPTY_FRAME_TILE,
-            WallType::Border   => &BRICK_TILE, 
+    /// Chipped or shot away since it was placed.
+    pub fn is_damaged(&self) -> bool {
+        self.variant != self.built || self.hit_points < self.built.flags().hit_points
+    }
+
+    /// Seconds since the last hit, only counted while damaged.
+    pub fn on_frame(&mut self, dt: f64) {
+        if self.is_damaged() {
+            self.since_hit += dt;
         }
     }
 
-    pub fn border(mut self) -> Self {   
-        self.variant = WallType::Border;
-        self
+    pub fn get_since_hit(&self) -> f64 {
+        self.since_hit
     }
 
-}
+    /// Back to the wall it was placed as, at full hit points.
+    pub fn restore(&mut self) {
+        *self = Wall::new(self.position).with_variant(self.built);
+    }
 
-pub fn generate_walls(column_count: u8, row_count: u8) -> Vec<Vec<Wall>> {
-    let mut walls = vec![];
+    pub fn blocks_tanks(&self) -> bool {
+        self.flags().blocks_tanks
+    }
 
-    for y in 0..row_count {
-        let mut row = vec![];
+    pub fn blocks_bullets(&self) -> bool {
+        self.flags().blocks_bullets
+    }
 
-        for x in 0..column_count {
-            let rng = rand::random::<u8>() % 6;
+    pub fn blocks_sight(&self) -> bool {
+        self.flags().blocks_sight
+    }
 
-            let w = match rng {
-                 0 | 1 => Wall::new([x as i32, y as i32]).brick(),
-                2     => Wall::new([x as i32, y as i32]).concrete(),
-                3     => Wall::new([x as i32, y as i32]).net(),
-                _     => Wall::new([x as i32, y as i32]).empty(),
-            };
-            row.push(w);
-        }
-        walls.push(row);
+    pub fn hides_tanks(&self) -> bool {
+        self.flags().hides_tanks
     }
 
-     // para el border
-    let w = column_count as usize;
-    let h = row_count as usize;
+    pub fn is_slippery(&self) -> bool {
+        self.flags().slippery
+    }
 
+    pub fn get_frame(&self) -> &[f64; 4] {
+        match self.variant {
+            WallType::Brick => match self.variant.flags().hit_points.saturating_sub(self.hit_points) {
+                0 => &BRICK_TILE,
+                1 => &BRICK_DAMAGED_1_TILE,
+                _ => &BRICK_DAMAGED_2_TILE,
+            },
+            WallType::Concrete if self.is_damaged() => &CONCRETE_DAMAGED_TILE,
+            WallType::Concrete => &CONCRETE_TILE,
+            WallType::Net | WallType::Forest => &NET_TILE,
+            // no sprites for these, frontends draw them as colored floor
+            WallType::Empty | WallType::Water | WallType::Ice => &EMPTY_FRAME_TILE,
+            WallType::Border   => &BRICK_TILE, 
+        }
+    }
 
-    // top y 
-    for x in 0..w {
-        walls[0][x]       = Wall::new([x as i32, 0]).border();
-        walls[h - 1][x]   = Wall::new([x as i32, (h - 1) as i32]).border();
+    pub fn border(self) -> Self {
+        self.with_variant(WallType::Border)
     }
-   
-    for y in 0..h {
-        walls[y][0]       = Wall::new([0, y as i32]).border();
-        walls[y][w - 1]   = Wall::new([(w - 1) as i32, y as i32]).border();
+
+    pub fn with_variant(mut self, variant: WallType) -> Self {
+        self.variant = variant;
+        self.built = variant;
+        self.hit_points = variant.flags().hit_points;
+        self
     }
 
-  
-    [
-        [0, 0],
-        [row_count as i32 - 1, 0],
-        [0, column_count as i32 - 1],
-        [row_count as i32 - 1, column_count as i32 - 1],
-    ]
-    .iter()
-    .for_each(|&[x, y]| {
-        
-    });
-
-    walls
 }
 
 pub fn carve_safe_zone(walls: &mut Vec<Vec<Wall>>, center: [i32; 2], radius: i32) {
@@ -173,4 +261,89 @@ pub fn carve_safe_zone(walls: &mut Vec<Vec<Wall>>, center: [i32; 2], radius: i32
     }
 }
 
-    
\ No newline at end of file
+    
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wall(variant: WallType) -> Wall {
+        Wall::new([1, 1]).with_variant(variant)
+    }
+
+    #[test]
+    fn damage_per_tile_type() {
+        let brick = WallType::Brick.flags();
+        assert_eq!((brick.damage_from(1), brick.damage_from(2), brick.damage_from(3)), (1, 2, 3));
+
+        // armor soaks up one point: only upgraded shots chip concrete
+        let concrete = WallType::Concrete.flags();
+        assert_eq!((concrete.damage_from(1), concrete.damage_from(2), concrete.damage_from(3)), (0, 1, 2));
+
+        for tile in [WallType::Border, WallType::Empty, WallType::Net, WallType::Water, WallType::Ice, WallType::Forest] {
+            assert_eq!(tile.flags().damage_from(3), 0, "{:?}", tile);
+            assert!(!tile.flags().is_destructible(), "{:?}", tile);
+        }
+        assert!(brick.is_destructible());
+        assert!(!concrete.is_destructible());
+    }
+
+    #[test]
+    fn brick_goes_through_its_damage_frames() {
+        let mut brick = wall(WallType::Brick);
+        assert_eq!(brick.get_hit_points(), 3);
+        assert_eq!(brick.get_frame(), &BRICK_TILE);
+
+        assert!(brick.damage(1));
+        assert_eq!(brick.get_hit_points(), 2);
+        assert_eq!(brick.get_frame(), &BRICK_DAMAGED_1_TILE);
+
+        assert!(brick.damage(1));
+        assert_eq!(brick.get_frame(), &BRICK_DAMAGED_2_TILE);
+
+        assert!(brick.damage(1));
+        assert_eq!(brick.variant(), WallType::Empty);
+        assert!(!brick.blocks_bullets());
+        assert!(!brick.damage(1));
+    }
+
+    #[test]
+    fn concrete_yields_to_upgraded_shots_only() {
+        let mut concrete = wall(WallType::Concrete);
+        assert!(!concrete.damage(1));
+        assert!(!concrete.is_damaged());
+        assert_eq!(concrete.get_frame(), &CONCRETE_TILE);
+
+        assert!(concrete.damage(2));
+        assert_eq!(concrete.get_hit_points(), 1);
+        assert_eq!(concrete.get_frame(), &CONCRETE_DAMAGED_TILE);
+
+        assert!(concrete.damage(3));
+        assert_eq!(concrete.variant(), WallType::Empty);
+    }
+
+    #[test]
+    fn regrowth_clock_runs_while_damaged() {
+        let mut brick = wall(WallType::Brick);
+        brick.on_frame(5.0);
+        assert_eq!(brick.get_since_hit(), 0.0);
+
+        brick.damage(1);
+        brick.on_frame(1.0);
+        brick.on_frame(0.5);
+        assert_eq!(brick.get_since_hit(), 1.5);
+
+        // another hit starts the wait over
+        brick.damage(2);
+        assert_eq!(brick.variant(), WallType::Empty);
+        assert_eq!(brick.get_since_hit(), 0.0);
+        brick.on_frame(2.0);
+        assert_eq!(brick.get_since_hit(), 2.0);
+
+        brick.restore();
+        assert_eq!(brick.variant(), WallType::Brick);
+        assert_eq!(brick.get_hit_points(), 3);
+        assert!(!brick.is_damaged());
+        brick.on_frame(1.0);
+        assert_eq!(brick.get_since_hit(), 0.0);
+    }
+}